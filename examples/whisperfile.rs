@@ -8,19 +8,24 @@ use glimpse_speech::{
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     let args: Vec<String> = std::env::args().collect();
 
-    let model_dir = PathBuf::from(
+    let binary_path = PathBuf::from(
         args.get(1)
             .map(|value| value.as_str())
-            .unwrap_or("models/parakeet-tdt-0.6b-v3-coreml"),
+            .unwrap_or("whisperfile"),
     );
-    let wav_path = PathBuf::from(
+    let model_path = PathBuf::from(
         args.get(2)
+            .map(|value| value.as_str())
+            .unwrap_or("models/whisper-medium-q4_1.bin"),
+    );
+    let wav_path = PathBuf::from(
+        args.get(3)
             .map(|value| value.as_str())
             .unwrap_or("samples/dots.wav"),
     );
 
-    let mut engine = WhisperfileEngine::new(PathBuf::from("whisperfile"));
-    engine.load_model_with_params(&model_dir, WhisperfileModelParams::default())?;
+    let mut engine = WhisperfileEngine::new(binary_path);
+    engine.load_model_with_params(&model_path, WhisperfileModelParams::default())?;
 
     let result = engine.transcribe_file(&wav_path, Some(WhisperfileInferenceParams::default()))?;
 
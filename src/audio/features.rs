@@ -0,0 +1,482 @@
+//! Log-mel spectrograms, the input features of Whisper, NeMo/Parakeet and most other
+//! speech models.
+
+use std::sync::Arc;
+
+use rustfft::num_complex::Complex;
+use rustfft::{Fft, FftPlanner};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Window {
+    /// `periodic` matches `torch.hann_window`'s default; NeMo uses the symmetric window.
+    Hann {
+        periodic: bool,
+    },
+    Hamming {
+        periodic: bool,
+    },
+    Rectangular,
+}
+
+/// How the signal is padded by `n_fft / 2` on both sides so frames are centered.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Padding {
+    /// Mirror the signal, like `torch.stft(center=True)`.
+    Reflect,
+    Zeros,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Normalization {
+    /// Natural log of the mel energies plus 2^-24.
+    None,
+    /// NeMo: natural log, then every mel bin shifted and scaled to zero mean and unit
+    /// variance over time.
+    PerFeature,
+    /// Whisper: log10, clamped to 8 below the loudest value, then `(x + 4) / 4`. Like
+    /// Whisper, the last STFT frame is dropped.
+    Whisper,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogMelConfig {
+    pub sample_rate: usize,
+    pub n_fft: usize,
+    /// Window length in samples, at most `n_fft`; the window is centered in the FFT.
+    pub win_length: usize,
+    pub hop_length: usize,
+    pub window: Window,
+    /// Mel bins, usually 80 or 128. Filters are Slaney-scaled and -normalized from 0 Hz to
+    /// Nyquist, like `librosa.filters.mel`.
+    pub n_mels: usize,
+    /// Pre-emphasis coefficient, e.g. `0.97`.
+    pub preemphasis: Option<f32>,
+    pub padding: Padding,
+    pub normalization: Normalization,
+}
+
+impl LogMelConfig {
+    /// Whisper's front-end with 80 bins (128 for large-v3 and turbo).
+    pub fn whisper(n_mels: usize) -> Self {
+        Self {
+            sample_rate: 16_000,
+            n_fft: 400,
+            win_length: 400,
+            hop_length: 160,
+            window: Window::Hann { periodic: true },
+            n_mels,
+            preemphasis: None,
+            padding: Padding::Reflect,
+            normalization: Normalization::Whisper,
+        }
+    }
+
+    /// NeMo's `AudioToMelSpectrogramPreprocessor` as configured for Parakeet and other
+    /// FastConformer models: 128 bins for Parakeet TDT, 80 for most CTC models.
+    pub fn nemo(n_mels: usize) -> Self {
+        Self {
+            sample_rate: 16_000,
+            n_fft: 512,
+            win_length: 400,
+            hop_length: 160,
+            window: Window::Hann { periodic: false },
+            n_mels,
+            preemphasis: Some(0.97),
+            padding: Padding::Zeros,
+            normalization: Normalization::PerFeature,
+        }
+    }
+}
+
+/// Log-mel features as `[n_mels, frames]`, row-major.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogMelSpectrogram {
+    n_mels: usize,
+    frames: usize,
+    data: Vec<f32>,
+}
+
+impl LogMelSpectrogram {
+    pub fn n_mels(&self) -> usize {
+        self.n_mels
+    }
+
+    pub fn frames(&self) -> usize {
+        self.frames
+    }
+
+    pub fn get(&self, mel: usize, frame: usize) -> f32 {
+        self.data[mel * self.frames + frame]
+    }
+
+    /// One mel bin over time.
+    pub fn row(&self, mel: usize) -> &[f32] {
+        &self.data[mel * self.frames..(mel + 1) * self.frames]
+    }
+
+    pub fn data(&self) -> &[f32] {
+        &self.data
+    }
+
+    pub fn into_data(self) -> Vec<f32> {
+        self.data
+    }
+}
+
+/// Computes log-mel spectrograms for one [`LogMelConfig`]. The window, filters and FFT
+/// plan are built once, so keep the extractor around between calls.
+pub struct LogMelExtractor {
+    config: LogMelConfig,
+    window: Vec<f32>,
+    filters: Vec<f32>,
+    fft: Arc<dyn Fft<f32>>,
+}
+
+impl LogMelExtractor {
+    pub fn new(config: LogMelConfig) -> Result<Self, Box<dyn std::error::Error>> {
+        if config.n_fft == 0 || config.hop_length == 0 || config.n_mels == 0 {
+            return Err(io_error("n_fft, hop_length and n_mels must be positive"));
+        }
+        if config.win_length == 0 || config.win_length > config.n_fft {
+            return Err(io_error(format!(
+                "win_length must be between 1 and n_fft ({}), got {}",
+                config.n_fft, config.win_length
+            )));
+        }
+        Ok(Self {
+            window: window(config.window, config.win_length),
+            filters: mel_filters(config.sample_rate, config.n_fft, config.n_mels),
+            fft: FftPlanner::new().plan_fft_forward(config.n_fft),
+            config,
+        })
+    }
+
+    pub fn config(&self) -> &LogMelConfig {
+        &self.config
+    }
+
+    /// Mel filterbank as `[n_mels, n_fft / 2 + 1]`, row-major.
+    pub fn filters(&self) -> &[f32] {
+        &self.filters
+    }
+
+    /// Features for 16 kHz mono samples (or whatever `sample_rate` the config is for).
+    /// There are `samples.len() / hop_length + 1` frames, one fewer for Whisper.
+    pub fn compute(&self, samples: &[f32]) -> LogMelSpectrogram {
+        let config = &self.config;
+        let signal = match config.preemphasis {
+            Some(coefficient) => preemphasize(samples, coefficient),
+            None => samples.to_vec(),
+        };
+        let padding = config.n_fft / 2;
+        let padded = pad(&signal, padding, config.padding);
+
+        let mut frames = signal.len() / config.hop_length + 1;
+        if config.normalization == Normalization::Whisper {
+            frames -= 1;
+        }
+        let bins = config.n_fft / 2 + 1;
+        let window_offset = (config.n_fft - config.win_length) / 2;
+        let mut data = vec![0.0f32; config.n_mels * frames];
+        let mut buffer = vec![Complex::new(0.0, 0.0); config.n_fft];
+        let mut power = vec![0.0f32; bins];
+
+        for frame in 0..frames {
+            let start = frame * config.hop_length + window_offset;
+            buffer.fill(Complex::new(0.0, 0.0));
+            for (n, weight) in self.window.iter().enumerate() {
+                buffer[window_offset + n].re = padded[start + n] * weight;
+            }
+            self.fft.process(&mut buffer);
+            for (value, bin) in power.iter_mut().zip(&buffer) {
+                *value = bin.norm_sqr();
+            }
+            for (mel, filter) in self.filters.chunks(bins).enumerate() {
+                data[mel * frames + frame] = filter
+                    .iter()
+                    .zip(&power)
+                    .map(|(weight, power)| weight * power)
+                    .sum();
+            }
+        }
+
+        normalize(&mut data, frames, config.normalization);
+        LogMelSpectrogram {
+            n_mels: config.n_mels,
+            frames,
+            data,
+        }
+    }
+}
+
+fn preemphasize(samples: &[f32], coefficient: f32) -> Vec<f32> {
+    let mut output = Vec::with_capacity(samples.len());
+    let mut previous = None;
+    for &sample in samples {
+        output.push(match previous {
+            Some(previous) => sample - coefficient * previous,
+            None => sample,
+        });
+        previous = Some(sample);
+    }
+    output
+}
+
+fn pad(signal: &[f32], padding: usize, mode: Padding) -> Vec<f32> {
+    let length = signal.len() as isize;
+    (-(padding as isize)..length + padding as isize)
+        .map(|index| {
+            let index = match mode {
+                Padding::Zeros => index,
+                Padding::Reflect if index < 0 => -index,
+                Padding::Reflect if index >= length => 2 * (length - 1) - index,
+                Padding::Reflect => index,
+            };
+            // Signals shorter than the padding run out of samples to mirror.
+            usize::try_from(index)
+                .ok()
+                .and_then(|index| signal.get(index))
+                .copied()
+                .unwrap_or(0.0)
+        })
+        .collect()
+}
+
+fn window(kind: Window, length: usize) -> Vec<f32> {
+    let (a0, periodic) = match kind {
+        Window::Hann { periodic } => (0.5, periodic),
+        Window::Hamming { periodic } => (0.54, periodic),
+        Window::Rectangular => return vec![1.0; length],
+    };
+    let denominator = if periodic { length } else { length - 1 }.max(1) as f64;
+    (0..length)
+        .map(|n| {
+            let phase = 2.0 * std::f64::consts::PI * n as f64 / denominator;
+            (a0 - (1.0 - a0) * phase.cos()) as f32
+        })
+        .collect()
+}
+
+fn mel_filters(sample_rate: usize, n_fft: usize, n_mels: usize) -> Vec<f32> {
+    const F_SP: f64 = 200.0 / 3.0;
+    const MIN_LOG_HZ: f64 = 1000.0;
+    let min_log_mel = MIN_LOG_HZ / F_SP;
+    let log_step = 6.4f64.ln() / 27.0;
+    let hz_to_mel = |hz: f64| {
+        if hz >= MIN_LOG_HZ {
+            min_log_mel + (hz / MIN_LOG_HZ).ln() / log_step
+        } else {
+            hz / F_SP
+        }
+    };
+    let mel_to_hz = |mel: f64| {
+        if mel >= min_log_mel {
+            MIN_LOG_HZ * (log_step * (mel - min_log_mel)).exp()
+        } else {
+            mel * F_SP
+        }
+    };
+
+    let nyquist = sample_rate as f64 / 2.0;
+    let bins = n_fft / 2 + 1;
+    let max_mel = hz_to_mel(nyquist);
+    let edges = (0..n_mels + 2)
+        .map(|index| mel_to_hz(max_mel * index as f64 / (n_mels + 1) as f64))
+        .collect::<Vec<_>>();
+
+    let mut filters = vec![0.0f32; n_mels * bins];
+    for mel in 0..n_mels {
+        let (lower, center, upper) = (edges[mel], edges[mel + 1], edges[mel + 2]);
+        let norm = 2.0 / (upper - lower);
+        for bin in 0..bins {
+            let hz = sample_rate as f64 * bin as f64 / n_fft as f64;
+            let rising = (hz - lower) / (center - lower);
+            let falling = (upper - hz) / (upper - center);
+            filters[mel * bins + bin] = (rising.min(falling).max(0.0) * norm) as f32;
+        }
+    }
+    filters
+}
+
+fn normalize(data: &mut [f32], frames: usize, normalization: Normalization) {
+    match normalization {
+        Normalization::None => {
+            for value in data.iter_mut() {
+                *value = (*value + f32::powi(2.0, -24)).ln();
+            }
+        }
+        Normalization::PerFeature => {
+            for value in data.iter_mut() {
+                *value = (*value + f32::powi(2.0, -24)).ln();
+            }
+            for row in data.chunks_mut(frames.max(1)) {
+                // Accumulating in f64 keeps constant rows (silence) exactly at zero.
+                let mean = row.iter().map(|&value| value as f64).sum::<f64>() / frames as f64;
+                let variance = row
+                    .iter()
+                    .map(|&value| (value as f64 - mean).powi(2))
+                    .sum::<f64>()
+                    / (frames.max(2) - 1) as f64;
+                let std = variance.sqrt() + 1e-5;
+                for value in row {
+                    *value = ((*value as f64 - mean) / std) as f32;
+                }
+            }
+        }
+        Normalization::Whisper => {
+            for value in data.iter_mut() {
+                *value = value.max(1e-10).log10();
+            }
+            let floor = data.iter().copied().fold(f32::NEG_INFINITY, f32::max) - 8.0;
+            for value in data.iter_mut() {
+                *value = (value.max(floor) + 4.0) / 4.0;
+            }
+        }
+    }
+}
+
+fn io_error(message: impl Into<String>) -> Box<dyn std::error::Error> {
+    std::io::Error::other(message.into()).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LogMelConfig, LogMelExtractor, Normalization, Padding, Window};
+
+    fn sine(hz: f32, seconds: f32) -> Vec<f32> {
+        let count = (16_000.0 * seconds) as usize;
+        (0..count)
+            .map(|n| (2.0 * std::f32::consts::PI * hz * n as f32 / 16_000.0).sin() * 0.5)
+            .collect()
+    }
+
+    #[test]
+    fn whisper_filters_match_the_published_filterbank() {
+        // Values from Whisper's `mel_filters.npz` (librosa.filters.mel(16000, 400, 80)).
+        let extractor = LogMelExtractor::new(LogMelConfig::whisper(80)).unwrap();
+        let filters = extractor.filters();
+
+        assert_eq!(filters.len(), 80 * 201);
+        assert_eq!(filters[0], 0.0);
+        assert!((filters[1] - 0.024_862_59).abs() < 1e-6, "{}", filters[1]);
+        assert_eq!(filters[2], 0.0);
+        assert!((filters[201 + 1] - 0.001_990_82).abs() < 1e-6);
+        assert!((filters[201 + 2] - 0.022_871_77).abs() < 1e-6);
+    }
+
+    #[test]
+    fn whisper_silence_is_the_familiar_constant() {
+        let extractor = LogMelExtractor::new(LogMelConfig::whisper(128)).unwrap();
+
+        let features = extractor.compute(&vec![0.0; 16_000]);
+
+        assert_eq!((features.n_mels(), features.frames()), (128, 100));
+        assert!(features.data().iter().all(|&value| value == -1.5));
+    }
+
+    #[test]
+    fn nemo_features_are_normalized_per_bin() {
+        let extractor = LogMelExtractor::new(LogMelConfig::nemo(80)).unwrap();
+
+        let features = extractor.compute(&sine(440.0, 1.0));
+
+        assert_eq!((features.n_mels(), features.frames()), (80, 101));
+        for mel in 0..80 {
+            let row = features.row(mel);
+            let mean = row.iter().sum::<f32>() / row.len() as f32;
+            assert!(mean.abs() < 1e-3, "bin {mel} mean {mean}");
+        }
+        let silence = extractor.compute(&[0.0; 1600]);
+        assert!(silence.data().iter().all(|&value| value == 0.0));
+    }
+
+    #[test]
+    fn tone_lands_in_the_matching_mel_bin() {
+        let extractor = LogMelExtractor::new(LogMelConfig {
+            normalization: Normalization::None,
+            ..LogMelConfig::whisper(80)
+        })
+        .unwrap();
+
+        let features = extractor.compute(&sine(1000.0, 0.5));
+        let frame = features.frames() / 2;
+        let loudest = (0..80)
+            .max_by(|&a, &b| features.get(a, frame).total_cmp(&features.get(b, frame)))
+            .unwrap();
+
+        // 1 kHz is mel 15 on the Slaney scale; bin centers are 0.5586 mel apart.
+        assert!((25..=26).contains(&loudest), "{loudest}");
+    }
+
+    #[test]
+    fn fft_path_matches_a_direct_dft() {
+        let config = LogMelConfig {
+            n_fft: 64,
+            win_length: 48,
+            hop_length: 16,
+            n_mels: 8,
+            window: Window::Hamming { periodic: false },
+            preemphasis: Some(0.9),
+            padding: Padding::Reflect,
+            normalization: Normalization::None,
+            ..LogMelConfig::nemo(8)
+        };
+        let extractor = LogMelExtractor::new(config).unwrap();
+        let samples = (0..200)
+            .map(|n| ((n * 37 % 101) as f32 / 50.0 - 1.0) * 0.3)
+            .collect::<Vec<_>>();
+
+        let features = extractor.compute(&samples);
+
+        // Frame 3 by hand: pre-emphasis, reflect padding, a centered window and a plain DFT.
+        let emphasized = (0..samples.len())
+            .map(|n| samples[n] - if n == 0 { 0.0 } else { 0.9 * samples[n - 1] })
+            .collect::<Vec<_>>();
+        let at = |index: isize| {
+            let index = if index < 0 { -index } else { index } as usize;
+            emphasized[index]
+        };
+        let window = (0..48)
+            .map(|n| 0.54 - 0.46 * (2.0 * std::f64::consts::PI * n as f64 / 47.0).cos())
+            .collect::<Vec<_>>();
+        let power = (0..33)
+            .map(|bin| {
+                let (mut re, mut im) = (0.0f64, 0.0f64);
+                for (n, weight) in window.iter().enumerate() {
+                    let position = 3 * 16 + 8 + n as isize - 32;
+                    let value = at(position) as f64 * weight;
+                    let angle = -2.0 * std::f64::consts::PI * (bin * (n + 8)) as f64 / 64.0;
+                    re += value * angle.cos();
+                    im += value * angle.sin();
+                }
+                re * re + im * im
+            })
+            .collect::<Vec<_>>();
+        for mel in 0..8 {
+            let filter = &extractor.filters()[mel * 33..(mel + 1) * 33];
+            let energy = filter
+                .iter()
+                .zip(&power)
+                .map(|(&weight, power)| weight as f64 * power)
+                .sum::<f64>();
+            let expected = (energy + 2f64.powi(-24)).ln() as f32;
+            assert!(
+                (features.get(mel, 3) - expected).abs() < 1e-3,
+                "mel {mel}: {} vs {expected}",
+                features.get(mel, 3)
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_a_window_longer_than_the_fft() {
+        let error = LogMelExtractor::new(LogMelConfig {
+            win_length: 600,
+            ..LogMelConfig::whisper(80)
+        })
+        .err()
+        .expect("invalid config");
+
+        assert!(error.to_string().contains("win_length"));
+    }
+}
@@ -0,0 +1,72 @@
+#[cfg(feature = "audio-features")]
+pub mod features;
+
+use std::path::Path;
+
+/// Requirements: 16 kHz, mono, PCM int16 WAV file.
+pub fn read_wav_samples(wav_path: &Path) -> Result<Vec<f32>, Box<dyn std::error::Error>> {
+    decode_wav(hound::WavReader::open(wav_path)?)
+}
+
+/// Same requirements as [`read_wav_samples`], for a WAV file already in memory.
+pub fn decode_wav_bytes(bytes: &[u8]) -> Result<Vec<f32>, Box<dyn std::error::Error>> {
+    decode_wav(hound::WavReader::new(std::io::Cursor::new(bytes))?)
+}
+
+fn decode_wav<R: std::io::Read>(
+    mut reader: hound::WavReader<R>,
+) -> Result<Vec<f32>, Box<dyn std::error::Error>> {
+    let spec = reader.spec();
+
+    if spec.channels != 1 {
+        return Err(format!("Expected 1 channel, found {}", spec.channels).into());
+    }
+
+    if spec.sample_rate != 16_000 {
+        return Err(format!(
+            "Expected 16000 Hz sample rate, found {} Hz",
+            spec.sample_rate
+        )
+        .into());
+    }
+
+    if spec.bits_per_sample != 16 {
+        return Err(format!(
+            "Expected 16 bits per sample, found {}",
+            spec.bits_per_sample
+        )
+        .into());
+    }
+
+    if spec.sample_format != hound::SampleFormat::Int {
+        return Err(format!("Expected Int sample format, found {:?}", spec.sample_format).into());
+    }
+
+    let samples: Result<Vec<f32>, _> = reader
+        .samples::<i16>()
+        .map(|sample| sample.map(|s| s as f32 / i16::MAX as f32))
+        .collect();
+
+    Ok(samples?)
+}
+
+/// Encodes 16 kHz mono f32 samples as an in-memory PCM int16 WAV file.
+pub fn encode_wav_samples(samples: &[f32]) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let spec = hound::WavSpec {
+        channels: 1,
+        sample_rate: 16_000,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    };
+
+    let mut cursor = std::io::Cursor::new(Vec::new());
+    let mut writer = hound::WavWriter::new(&mut cursor, spec)?;
+    for sample in samples {
+        let clamped = sample.clamp(-1.0, 1.0);
+        let pcm = (clamped * i16::MAX as f32).round() as i16;
+        writer.write_sample(pcm)?;
+    }
+    writer.finalize()?;
+
+    Ok(cursor.into_inner())
+}
@@ -0,0 +1,142 @@
+use std::collections::HashSet;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver};
+use std::sync::Arc;
+use std::thread;
+
+use crate::pool::EnginePool;
+use crate::{TranscriptionEngine, TranscriptionResult};
+
+/// Outcome of one batch input.
+#[derive(Debug)]
+pub struct BatchItem {
+    pub input: PathBuf,
+    pub result: Result<TranscriptionResult, Box<dyn std::error::Error>>,
+}
+
+/// Transcribes many files in parallel on an [`EnginePool`].
+pub struct BatchTranscriber<E> {
+    pool: Arc<EnginePool<E>>,
+    skip: HashSet<PathBuf>,
+}
+
+impl<E: 'static> BatchTranscriber<E> {
+    pub fn new(pool: EnginePool<E>) -> Self {
+        Self {
+            pool: Arc::new(pool),
+            skip: HashSet::new(),
+        }
+    }
+
+    /// Inputs to leave out, e.g. the ones a previous interrupted run already finished.
+    pub fn with_skip_list<I, P>(mut self, inputs: I) -> Self
+    where
+        I: IntoIterator<Item = P>,
+        P: Into<PathBuf>,
+    {
+        self.skip.extend(inputs.into_iter().map(Into::into));
+        self
+    }
+
+    /// Runs `transcribe` for every input not on the skip list. Results stream back in
+    /// completion order; a failing or panicking item does not stop the batch.
+    pub fn run<F>(&self, inputs: Vec<PathBuf>, transcribe: F) -> BatchResults
+    where
+        F: Fn(&mut E, &Path) -> Result<TranscriptionResult, Box<dyn std::error::Error>>
+            + Send
+            + Sync
+            + 'static,
+    {
+        let total = inputs.len();
+        let pending = inputs
+            .into_iter()
+            .filter(|input| !self.skip.contains(input))
+            .collect::<Vec<_>>();
+        let skipped = total - pending.len();
+        let (results, receiver) = mpsc::channel();
+        let pool = Arc::clone(&self.pool);
+        let transcribe = Arc::new(transcribe);
+
+        // Submitting blocks once the pool queue is full, so it runs off the caller's thread.
+        thread::spawn(move || {
+            for input in pending {
+                let results_for_task = results.clone();
+                let transcribe = Arc::clone(&transcribe);
+                let task_input = input.clone();
+                let submitted = pool.execute(move |engine: &mut E| {
+                    let outcome = catch_unwind(AssertUnwindSafe(|| {
+                        transcribe(engine, &task_input).map_err(|error| error.to_string())
+                    }))
+                    .unwrap_or_else(|_| Err("engine panicked".to_string()));
+                    let _ = results_for_task.send((task_input, outcome));
+                });
+                if let Err(error) = submitted {
+                    let _ = results.send((input, Err(error.to_string())));
+                }
+            }
+        });
+
+        BatchResults {
+            receiver,
+            pending: total - skipped,
+            skipped,
+        }
+    }
+}
+
+impl<E> BatchTranscriber<E>
+where
+    E: TranscriptionEngine + 'static,
+    E::InferenceParams: Clone + Send + Sync,
+{
+    /// Transcribes WAV files with [`TranscriptionEngine::transcribe_file`].
+    pub fn transcribe_files(
+        &self,
+        inputs: Vec<PathBuf>,
+        params: Option<E::InferenceParams>,
+    ) -> BatchResults {
+        self.run(inputs, move |engine, input| {
+            engine.transcribe_file(input, params.clone())
+        })
+    }
+}
+
+/// Iterator over [`BatchItem`]s in completion order.
+pub struct BatchResults {
+    receiver: Receiver<(PathBuf, Result<TranscriptionResult, String>)>,
+    pending: usize,
+    skipped: usize,
+}
+
+impl BatchResults {
+    /// Inputs left out because they were on the skip list.
+    pub fn skipped(&self) -> usize {
+        self.skipped
+    }
+
+    /// Items not yet yielded.
+    pub fn remaining(&self) -> usize {
+        self.pending
+    }
+}
+
+impl Iterator for BatchResults {
+    type Item = BatchItem;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.pending == 0 {
+            return None;
+        }
+        let (input, result) = self.receiver.recv().ok()?;
+        self.pending -= 1;
+        Some(BatchItem {
+            input,
+            result: result.map_err(|message| std::io::Error::other(message).into()),
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.pending))
+    }
+}
@@ -0,0 +1,74 @@
+use std::path::PathBuf;
+
+use glimpse_speech::engines::any::{available_engines, engine_factory, EngineConfig};
+use glimpse_speech::server::{ServerConfig, TranscriptionServer, ACCEPTED_AUDIO_FORMAT};
+
+const USAGE: &str =
+    "usage: glimpse-server --engine <name> --model <path> [--binary <whisperfile>] \
+[--base-url <url>] [--api-key <key>] [--host <host>] [--port <port>] \
+[--concurrency <n>] [--queue <n>] [--max-upload-mb <n>]";
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let (engine, server) = parse_args(std::env::args().skip(1))?;
+
+    let model_name = engine
+        .model_path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| engine.engine.clone());
+    let factory = engine_factory(&engine)?;
+    let server = TranscriptionServer::start(
+        ServerConfig {
+            model_name,
+            ..server
+        },
+        factory,
+    )?;
+
+    if let Some(address) = server.local_addr() {
+        eprintln!("glimpse-server listening on http://{address}/v1");
+    }
+    server.run();
+    Ok(())
+}
+
+fn parse_args(
+    mut args: impl Iterator<Item = String>,
+) -> Result<(EngineConfig, ServerConfig), Box<dyn std::error::Error>> {
+    let mut engine = EngineConfig::default();
+    let mut server = ServerConfig::default();
+    let mut model_path = None;
+
+    while let Some(flag) = args.next() {
+        if flag == "-h" || flag == "--help" {
+            println!(
+                "{USAGE}\navailable engines: {}\naccepted uploads: {ACCEPTED_AUDIO_FORMAT} \
+(other formats get HTTP 415)",
+                available_engines().join(", ")
+            );
+            std::process::exit(0);
+        }
+        let value = args
+            .next()
+            .ok_or_else(|| format!("missing value for {flag}\n{USAGE}"))?;
+        match flag.as_str() {
+            "--engine" => engine.engine = value,
+            "--model" => model_path = Some(PathBuf::from(value)),
+            "--binary" => engine.binary_path = Some(PathBuf::from(value)),
+            "--base-url" => engine.base_url = Some(value),
+            "--api-key" => engine.api_key = Some(value),
+            "--host" => server.host = value,
+            "--port" => server.port = value.parse()?,
+            "--concurrency" => server.max_concurrency = value.parse()?,
+            "--queue" => server.queue_capacity = value.parse()?,
+            "--max-upload-mb" => server.max_upload_bytes = value.parse::<usize>()? * 1024 * 1024,
+            other => return Err(format!("unknown argument {other}\n{USAGE}").into()),
+        }
+    }
+
+    if engine.engine.is_empty() {
+        return Err(format!("--engine is required\n{USAGE}").into());
+    }
+    engine.model_path = model_path.ok_or_else(|| format!("--model is required\n{USAGE}"))?;
+    Ok((engine, server))
+}
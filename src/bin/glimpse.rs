@@ -0,0 +1,483 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::time::Duration;
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use glimpse_speech::audio::read_wav_samples;
+use glimpse_speech::batch::BatchTranscriber;
+use glimpse_speech::engines::any::{
+    available_engines, engine_factory, load_engine, AnyEngine, AnyInferenceParams, EngineConfig,
+};
+use glimpse_speech::format::{render_srt, render_tsv, render_vtt};
+use glimpse_speech::pool::{EnginePool, PoolOptions};
+use glimpse_speech::{SpeakerTurn, TranscriptionResult, TranscriptionSegment};
+use indicatif::{ProgressBar, ProgressStyle};
+
+/// Transcribe 16 kHz mono WAV files with any engine compiled into this build.
+#[derive(Parser)]
+#[command(name = "glimpse", version)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Transcribe one file to stdout or to --output-dir.
+    Transcribe {
+        file: PathBuf,
+        #[command(flatten)]
+        engine: EngineArgs,
+        #[command(flatten)]
+        options: TranscribeArgs,
+    },
+    /// Transcribe many files (directories are searched for .wav files), writing
+    /// `<name>.<format>` into --output-dir or next to each input. Inputs that would
+    /// write the same output file are rejected.
+    Batch {
+        #[arg(required = true)]
+        inputs: Vec<PathBuf>,
+        #[command(flatten)]
+        engine: EngineArgs,
+        #[command(flatten)]
+        options: TranscribeArgs,
+        /// Files transcribed at the same time, each with its own engine.
+        #[arg(long, short, default_value_t = 1)]
+        jobs: usize,
+        /// Leave out inputs whose output file already exists, e.g. to resume a run.
+        #[arg(long)]
+        skip_existing: bool,
+    },
+    /// Print the most likely spoken languages.
+    DetectLanguage {
+        file: PathBuf,
+        #[command(flatten)]
+        engine: EngineArgs,
+        /// Number of candidates to print.
+        #[arg(long, default_value_t = 5)]
+        top: usize,
+        #[arg(long)]
+        threads: Option<usize>,
+    },
+    /// Print speaker turns.
+    Diarize {
+        file: PathBuf,
+        #[command(flatten)]
+        engine: EngineArgs,
+        /// Expected number of speakers, if known.
+        #[arg(long)]
+        speakers: Option<u32>,
+        #[arg(long, value_enum, default_value_t = OutputFormat::Txt)]
+        format: OutputFormat,
+    },
+    /// Print the version and the engines compiled into this build.
+    Info,
+}
+
+#[derive(Args)]
+struct EngineArgs {
+    /// Engine name; see `glimpse info`.
+    #[arg(long, short)]
+    engine: String,
+    /// Model file or directory (model name for the remote engine).
+    #[arg(long, short)]
+    model: PathBuf,
+    /// whisperfile binary.
+    #[arg(long)]
+    binary: Option<PathBuf>,
+    /// API root for the remote engine, e.g. http://gpu-box:8000/v1.
+    #[arg(long)]
+    base_url: Option<String>,
+    #[arg(long, env = "GLIMPSE_API_KEY", hide_env_values = true)]
+    api_key: Option<String>,
+    /// Speaker diarization models (parakeet engine).
+    #[arg(long)]
+    diarization_model_dir: Option<PathBuf>,
+    /// Let the parakeet engine download missing tokenizer files for --vocabulary.
+    #[arg(long)]
+    allow_network: bool,
+}
+
+impl EngineArgs {
+    fn config(&self) -> EngineConfig {
+        EngineConfig {
+            engine: self.engine.clone(),
+            model_path: self.model.clone(),
+            binary_path: self.binary.clone(),
+            base_url: self.base_url.clone(),
+            api_key: self.api_key.clone(),
+            diarization_model_dir: self.diarization_model_dir.clone(),
+            allow_network: self.allow_network,
+        }
+    }
+
+    fn load(&self) -> Result<Box<dyn AnyEngine>, Box<dyn std::error::Error>> {
+        load_engine(&self.config())
+    }
+}
+
+#[derive(Args)]
+struct TranscribeArgs {
+    #[arg(long, short)]
+    language: Option<String>,
+    /// Translate to English (engines that support it).
+    #[arg(long)]
+    translate: bool,
+    #[arg(long)]
+    prompt: Option<String>,
+    /// Comma-separated terms to bias recognition towards.
+    #[arg(long, value_delimiter = ',')]
+    vocabulary: Vec<String>,
+    /// Fix near-misses of --vocabulary terms in the output (Whisper-style engines).
+    #[arg(long)]
+    correct_vocabulary: bool,
+    #[arg(long)]
+    temperature: Option<f32>,
+    #[arg(long)]
+    word_timestamps: bool,
+    #[arg(long, short, value_enum, default_value_t = OutputFormat::Txt)]
+    format: OutputFormat,
+    /// Write `<name>.<format>` files here instead of printing.
+    #[arg(long, short)]
+    output_dir: Option<PathBuf>,
+    /// CPU threads for in-process engines.
+    #[arg(long, short)]
+    threads: Option<usize>,
+}
+
+impl TranscribeArgs {
+    fn inference_params(&self) -> AnyInferenceParams {
+        AnyInferenceParams {
+            language: self.language.clone(),
+            translate: self.translate,
+            prompt: self.prompt.clone(),
+            vocabulary: self.vocabulary.clone(),
+            correct_vocabulary: self.correct_vocabulary,
+            temperature: self.temperature,
+            word_timestamps: self.word_timestamps,
+            threads: self.threads,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum OutputFormat {
+    Txt,
+    Json,
+    Srt,
+    Vtt,
+    Tsv,
+}
+
+impl OutputFormat {
+    fn extension(&self) -> &'static str {
+        match self {
+            OutputFormat::Txt => "txt",
+            OutputFormat::Json => "json",
+            OutputFormat::Srt => "srt",
+            OutputFormat::Vtt => "vtt",
+            OutputFormat::Tsv => "tsv",
+        }
+    }
+
+    fn render(&self, result: &TranscriptionResult) -> Result<String, Box<dyn std::error::Error>> {
+        let segments = result.segments.clone().unwrap_or_else(|| {
+            vec![TranscriptionSegment {
+                start: 0.0,
+                end: 0.0,
+                text: result.text.clone(),
+                speaker: None,
+            }]
+        });
+        Ok(match self {
+            OutputFormat::Txt => format!("{}\n", result.text),
+            OutputFormat::Json => format!("{}\n", serde_json::to_string_pretty(result)?),
+            OutputFormat::Srt => render_srt(&segments),
+            OutputFormat::Vtt => render_vtt(&segments),
+            OutputFormat::Tsv => render_tsv(&segments),
+        })
+    }
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    match run(cli.command) {
+        Ok(code) => code,
+        Err(error) => {
+            eprintln!("error: {error}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(command: Command) -> Result<ExitCode, Box<dyn std::error::Error>> {
+    match command {
+        Command::Transcribe {
+            file,
+            engine,
+            options,
+        } => {
+            let mut engine = with_spinner("loading model", || engine.load())?;
+            let samples = read_wav_samples(&file)?;
+            let result = with_spinner(&format!("transcribing {}", file.display()), || {
+                engine.transcribe_samples(samples, &options.inference_params())
+            })?;
+            emit(&file, &result, &options)?;
+            Ok(ExitCode::SUCCESS)
+        }
+        Command::Batch {
+            inputs,
+            engine,
+            options,
+            jobs,
+            skip_existing,
+        } => batch(&inputs, &engine, &options, jobs, skip_existing),
+        Command::DetectLanguage {
+            file,
+            engine,
+            top,
+            threads,
+        } => {
+            let mut engine = with_spinner("loading model", || engine.load())?;
+            let samples = read_wav_samples(&file)?;
+            let params = AnyInferenceParams {
+                threads,
+                ..Default::default()
+            };
+            for (language, probability) in
+                engine.detect_language(&samples, &params)?.iter().take(top)
+            {
+                println!("{language}\t{probability:.4}");
+            }
+            Ok(ExitCode::SUCCESS)
+        }
+        Command::Diarize {
+            file,
+            engine,
+            speakers,
+            format,
+        } => {
+            let mut engine = with_spinner("loading model", || engine.load())?;
+            let turns = with_spinner(&format!("diarizing {}", file.display()), || {
+                engine.diarize_file(&file, speakers)
+            })?;
+            print!("{}", render_turns(&turns, format)?);
+            Ok(ExitCode::SUCCESS)
+        }
+        Command::Info => {
+            println!("glimpse {}", env!("CARGO_PKG_VERSION"));
+            let engines = available_engines();
+            if engines.is_empty() {
+                println!("engines: none (rebuild with e.g. --features cli,whisper)");
+            } else {
+                println!("engines: {}", engines.join(", "));
+            }
+            println!("formats: txt, json, srt, vtt, tsv");
+            #[cfg(feature = "whisper")]
+            println!(
+                "whisper: {}",
+                glimpse_speech::engines::whisper::WhisperEngine::backend_info()
+            );
+            Ok(ExitCode::SUCCESS)
+        }
+    }
+}
+
+fn batch(
+    inputs: &[PathBuf],
+    engine: &EngineArgs,
+    options: &TranscribeArgs,
+    jobs: usize,
+    skip_existing: bool,
+) -> Result<ExitCode, Box<dyn std::error::Error>> {
+    let files = collect_wav_files(inputs)?;
+    if files.is_empty() {
+        return Err("no .wav files found".into());
+    }
+    let mut outputs = HashMap::new();
+    for file in &files {
+        let output = output_path(file, options, true)?;
+        if let Some(previous) = outputs.insert(output.clone(), file) {
+            return Err(format!(
+                "{} and {} would both be written to {}; rename one or run them separately",
+                previous.display(),
+                file.display(),
+                output.display()
+            )
+            .into());
+        }
+    }
+    let skip_list = if skip_existing {
+        files
+            .iter()
+            .filter(|file| output_path(file, options, true).is_ok_and(|path| path.exists()))
+            .cloned()
+            .collect()
+    } else {
+        Vec::new()
+    };
+    if skip_list.len() == files.len() {
+        eprintln!("all {} files already have output", files.len());
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    let pool = with_spinner("loading model", || {
+        EnginePool::new(
+            PoolOptions {
+                workers: jobs.max(1),
+                ..Default::default()
+            },
+            engine_factory(&engine.config())?,
+        )
+    })?;
+    let params = options.inference_params();
+    let results = BatchTranscriber::new(pool)
+        .with_skip_list(skip_list)
+        .run(files.clone(), move |engine, file| {
+            engine.transcribe_samples(read_wav_samples(file)?, &params)
+        });
+
+    let skipped = results.skipped();
+    let progress = ProgressBar::new(results.remaining() as u64).with_style(
+        ProgressStyle::with_template("{bar:30} {pos}/{len} {elapsed_precise} {wide_msg}")
+            .expect("progress template is valid"),
+    );
+    let mut failures = 0;
+    for item in results {
+        progress.set_message(item.input.display().to_string());
+        let outcome = item.result.and_then(|result| {
+            progress.suspend(|| print_warnings(&item.input, &result));
+            write_output(&item.input, &result, options, true)
+        });
+        if let Err(error) = outcome {
+            failures += 1;
+            progress.suspend(|| eprintln!("error: {}: {error}", item.input.display()));
+        }
+        progress.inc(1);
+    }
+    progress.finish_and_clear();
+
+    let attempted = files.len() - skipped;
+    if skipped > 0 {
+        eprintln!("skipped {skipped} files with existing output");
+    }
+    eprintln!("transcribed {} of {attempted} files", attempted - failures);
+    Ok(if failures == 0 {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    })
+}
+
+fn collect_wav_files(inputs: &[PathBuf]) -> Result<Vec<PathBuf>, Box<dyn std::error::Error>> {
+    let mut files = Vec::new();
+    for input in inputs {
+        if input.is_dir() {
+            let mut entries = std::fs::read_dir(input)?
+                .map(|entry| entry.map(|entry| entry.path()))
+                .collect::<Result<Vec<_>, _>>()?;
+            entries.retain(|path| {
+                path.extension()
+                    .is_some_and(|extension| extension.eq_ignore_ascii_case("wav"))
+            });
+            entries.sort();
+            files.extend(entries);
+        } else {
+            files.push(input.clone());
+        }
+    }
+    Ok(files)
+}
+
+/// Prints to stdout unless `--output-dir` is set.
+fn emit(
+    file: &Path,
+    result: &TranscriptionResult,
+    options: &TranscribeArgs,
+) -> Result<(), Box<dyn std::error::Error>> {
+    print_warnings(file, result);
+    if options.output_dir.is_some() {
+        write_output(file, result, options, false)
+    } else {
+        print!("{}", options.format.render(result)?);
+        Ok(())
+    }
+}
+
+fn print_warnings(file: &Path, result: &TranscriptionResult) {
+    for warning in &result.warnings {
+        eprintln!("warning: {}: {warning}", file.display());
+    }
+}
+
+/// Writes `<stem>.<ext>` into `--output-dir`, or next to the input when `beside_input`.
+fn write_output(
+    file: &Path,
+    result: &TranscriptionResult,
+    options: &TranscribeArgs,
+    beside_input: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let path = output_path(file, options, beside_input)?;
+    if let Some(directory) = path.parent() {
+        std::fs::create_dir_all(directory)?;
+    }
+    std::fs::write(&path, options.format.render(result)?)?;
+    Ok(())
+}
+
+fn output_path(
+    file: &Path,
+    options: &TranscribeArgs,
+    beside_input: bool,
+) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    let directory = match (&options.output_dir, beside_input) {
+        (Some(directory), _) => directory.clone(),
+        (None, true) => file.parent().map(Path::to_path_buf).unwrap_or_default(),
+        (None, false) => return Err("no output directory".into()),
+    };
+    let stem = file
+        .file_stem()
+        .ok_or_else(|| format!("{} has no file name", file.display()))?;
+    let mut path = directory.join(stem);
+    path.set_extension(options.format.extension());
+    Ok(path)
+}
+
+fn render_turns(
+    turns: &[SpeakerTurn],
+    format: OutputFormat,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let as_segments = || {
+        turns
+            .iter()
+            .map(|turn| TranscriptionSegment {
+                start: turn.start,
+                end: turn.end,
+                text: turn.speaker.clone(),
+                speaker: None,
+            })
+            .collect::<Vec<_>>()
+    };
+    Ok(match format {
+        OutputFormat::Json => format!("{}\n", serde_json::to_string_pretty(turns)?),
+        OutputFormat::Srt => render_srt(&as_segments()),
+        OutputFormat::Vtt => render_vtt(&as_segments()),
+        OutputFormat::Tsv => render_tsv(&as_segments()),
+        OutputFormat::Txt => turns
+            .iter()
+            .map(|turn| format!("[{:.2}s - {:.2}s] {}\n", turn.start, turn.end, turn.speaker))
+            .collect(),
+    })
+}
+
+fn with_spinner<T>(
+    message: &str,
+    work: impl FnOnce() -> Result<T, Box<dyn std::error::Error>>,
+) -> Result<T, Box<dyn std::error::Error>> {
+    let spinner = ProgressBar::new_spinner().with_message(message.to_string());
+    spinner.enable_steady_tick(Duration::from_millis(100));
+    let result = work();
+    spinner.finish_and_clear();
+    result
+}
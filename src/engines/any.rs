@@ -0,0 +1,367 @@
+use std::path::{Path, PathBuf};
+
+#[cfg(any(feature = "parakeet", feature = "whisperfile", feature = "remote"))]
+use crate::vocabulary::Vocabulary;
+use crate::{SpeakerTurn, TranscriptionResult};
+
+/// Inference options understood by every engine. Options an engine has no equivalent
+/// for are ignored, except `translate`, which fails on engines that cannot translate.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AnyInferenceParams {
+    pub language: Option<String>,
+    pub translate: bool,
+    /// Free-form prompt for engines that accept one; never treated as vocabulary.
+    pub prompt: Option<String>,
+    /// Names and terms to recognize. Fluid engines boost them while decoding;
+    /// Whisper-style engines get them in the prompt.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub vocabulary: Vec<String>,
+    /// Also replace near-misses of `vocabulary` terms in the output text (Whisper-style
+    /// engines only).
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub correct_vocabulary: bool,
+    pub temperature: Option<f32>,
+    /// Ask for word-level timings where the engine supports them.
+    pub word_timestamps: bool,
+    /// CPU threads for engines that run inference in-process.
+    pub threads: Option<usize>,
+}
+
+/// Engine-agnostic handle used by the server and CLI to drive any engine by name.
+pub trait AnyEngine {
+    fn transcribe_samples(
+        &mut self,
+        samples: Vec<f32>,
+        params: &AnyInferenceParams,
+    ) -> Result<TranscriptionResult, Box<dyn std::error::Error>>;
+
+    /// Language probabilities, most likely first.
+    fn detect_language(
+        &mut self,
+        samples: &[f32],
+        params: &AnyInferenceParams,
+    ) -> Result<Vec<(String, f32)>, Box<dyn std::error::Error>> {
+        let _ = (samples, params);
+        Err(io_error("this engine does not support language detection"))
+    }
+
+    fn diarize_file(
+        &mut self,
+        wav_path: &Path,
+        speaker_count: Option<u32>,
+    ) -> Result<Vec<SpeakerTurn>, Box<dyn std::error::Error>> {
+        let _ = (wav_path, speaker_count);
+        Err(io_error("this engine does not support diarization"))
+    }
+}
+
+/// What to load: the engine name plus the options the individual engines need.
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EngineConfig {
+    /// One of [`available_engines`].
+    pub engine: String,
+    /// Model file or directory; the model name for the `remote` engine.
+    pub model_path: PathBuf,
+    /// whisperfile binary (`whisperfile` engine).
+    pub binary_path: Option<PathBuf>,
+    /// API root such as `http://gpu-box:8000/v1` (`remote` engine).
+    pub base_url: Option<String>,
+    /// Bearer token (`remote` engine).
+    pub api_key: Option<String>,
+    /// Speaker diarization models (`parakeet` engine).
+    pub diarization_model_dir: Option<PathBuf>,
+    /// Allow downloads of missing tokenizer files (`parakeet` engine).
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub allow_network: bool,
+}
+
+/// Engine names compiled into this build.
+pub fn available_engines() -> Vec<&'static str> {
+    vec![
+        #[cfg(feature = "whisper")]
+        "whisper",
+        #[cfg(feature = "parakeet")]
+        "parakeet",
+        #[cfg(feature = "onnx")]
+        "parakeet-onnx",
+        #[cfg(feature = "whisperfile")]
+        "whisperfile",
+        #[cfg(feature = "remote")]
+        "remote",
+    ]
+}
+
+/// Creates the engine named in `config` and loads its model.
+pub fn load_engine(
+    config: &EngineConfig,
+) -> Result<Box<dyn AnyEngine>, Box<dyn std::error::Error>> {
+    match config.engine.as_str() {
+        #[cfg(feature = "whisper")]
+        "whisper" => {
+            use crate::TranscriptionEngine;
+            let mut engine = super::whisper::WhisperEngine::new();
+            engine.load_model(&config.model_path)?;
+            Ok(Box::new(engine))
+        }
+        #[cfg(feature = "parakeet")]
+        "parakeet" => {
+            use crate::TranscriptionEngine;
+            let mut engine = super::parakeet::ParakeetEngine::new();
+            engine.load_model_with_params(
+                &config.model_path,
+                super::parakeet::ParakeetModelParams {
+                    diarization_model_dir: config.diarization_model_dir.clone(),
+                    allow_network: config.allow_network,
+                    ..Default::default()
+                },
+            )?;
+            Ok(Box::new(engine))
+        }
+        #[cfg(feature = "onnx")]
+        "parakeet-onnx" => {
+            use crate::TranscriptionEngine;
+            let mut engine = super::parakeet_onnx::ParakeetOnnxEngine::new();
+            engine.load_model(&config.model_path)?;
+            Ok(Box::new(engine))
+        }
+        #[cfg(feature = "whisperfile")]
+        "whisperfile" => {
+            use crate::TranscriptionEngine;
+            let binary_path = config
+                .binary_path
+                .clone()
+                .ok_or_else(|| io_error("the whisperfile engine needs a binary path"))?;
+            let mut engine = super::whisperfile::WhisperfileEngine::new(binary_path);
+            engine.load_model_with_params(
+                &config.model_path,
+                super::whisperfile::WhisperfileModelParams {
+                    port: 0,
+                    ..Default::default()
+                },
+            )?;
+            Ok(Box::new(engine))
+        }
+        #[cfg(feature = "remote")]
+        "remote" => {
+            use crate::TranscriptionEngine;
+            let mut engine = match config.base_url.as_deref() {
+                Some(base_url) => super::remote::RemoteEngine::new(base_url),
+                None => super::remote::RemoteEngine::default(),
+            };
+            engine.load_model_with_params(
+                &config.model_path,
+                super::remote::RemoteModelParams {
+                    api_key: config.api_key.clone(),
+                    ..Default::default()
+                },
+            )?;
+            Ok(Box::new(engine))
+        }
+        other => Err(io_error(format!(
+            "engine '{other}' is not available in this build (available: {})",
+            available_engines().join(", ")
+        ))),
+    }
+}
+
+/// Builds engines for `config` on demand, e.g. one per [`crate::pool::EnginePool`] worker.
+pub type EngineFactory =
+    Box<dyn Fn() -> Result<Box<dyn AnyEngine>, Box<dyn std::error::Error>> + Send + Sync>;
+
+/// Like [`load_engine`], but for engines that can share a loaded model (currently
+/// `whisper`) the model is loaded once here and every engine built by the factory
+/// gets its own session on it.
+pub fn engine_factory(config: &EngineConfig) -> Result<EngineFactory, Box<dyn std::error::Error>> {
+    match config.engine.as_str() {
+        #[cfg(feature = "whisper")]
+        "whisper" => {
+            let model = super::whisper::WhisperModel::load(&config.model_path, Default::default())?;
+            Ok(Box::new(move || {
+                Ok(Box::new(model.session()?) as Box<dyn AnyEngine>)
+            }))
+        }
+        _ => {
+            let config = config.clone();
+            Ok(Box::new(move || load_engine(&config)))
+        }
+    }
+}
+
+/// Fixes near-misses of vocabulary terms for engines that can only be biased through
+/// their prompt, if `params.correct_vocabulary` asks for it.
+#[cfg(any(feature = "whisperfile", feature = "remote"))]
+fn correct_vocabulary(
+    mut result: TranscriptionResult,
+    vocabulary: &Vocabulary,
+    params: &AnyInferenceParams,
+) -> TranscriptionResult {
+    if !params.correct_vocabulary || vocabulary.is_empty() {
+        return result;
+    }
+    result.text = vocabulary.correct(&result.text);
+    for segment in result.segments.iter_mut().flatten() {
+        segment.text = vocabulary.correct(&segment.text);
+    }
+    result
+}
+
+#[cfg(feature = "whisper")]
+impl AnyEngine for super::whisper::WhisperEngine {
+    fn transcribe_samples(
+        &mut self,
+        samples: Vec<f32>,
+        params: &AnyInferenceParams,
+    ) -> Result<TranscriptionResult, Box<dyn std::error::Error>> {
+        let params = super::whisper::WhisperInferenceParams {
+            language: params.language.clone(),
+            translate: params.translate,
+            initial_prompt: params.prompt.clone(),
+            n_threads: params.threads.map(|threads| threads as i32),
+            word_timestamps: params.word_timestamps,
+            vocabulary: params.vocabulary.clone(),
+            correct_vocabulary: params.correct_vocabulary,
+            ..Default::default()
+        };
+        crate::TranscriptionEngine::transcribe_samples(self, samples, Some(params))
+    }
+
+    fn detect_language(
+        &mut self,
+        samples: &[f32],
+        params: &AnyInferenceParams,
+    ) -> Result<Vec<(String, f32)>, Box<dyn std::error::Error>> {
+        super::whisper::WhisperEngine::detect_language(self, samples, params.threads.unwrap_or(4))
+    }
+}
+
+#[cfg(feature = "parakeet")]
+impl AnyEngine for super::parakeet::ParakeetEngine {
+    fn transcribe_samples(
+        &mut self,
+        samples: Vec<f32>,
+        params: &AnyInferenceParams,
+    ) -> Result<TranscriptionResult, Box<dyn std::error::Error>> {
+        let params = parakeet_params(params)?;
+        crate::TranscriptionEngine::transcribe_samples(self, samples, Some(params))
+    }
+
+    fn diarize_file(
+        &mut self,
+        wav_path: &Path,
+        speaker_count: Option<u32>,
+    ) -> Result<Vec<SpeakerTurn>, Box<dyn std::error::Error>> {
+        super::parakeet::ParakeetEngine::diarize_file(self, wav_path, speaker_count)
+    }
+}
+
+#[cfg(feature = "onnx")]
+impl AnyEngine for super::parakeet_onnx::ParakeetOnnxEngine {
+    fn transcribe_samples(
+        &mut self,
+        samples: Vec<f32>,
+        params: &AnyInferenceParams,
+    ) -> Result<TranscriptionResult, Box<dyn std::error::Error>> {
+        let params = parakeet_params(params)?;
+        crate::TranscriptionEngine::transcribe_samples(self, samples, Some(params))
+    }
+}
+
+#[cfg(feature = "parakeet")]
+fn parakeet_params(
+    params: &AnyInferenceParams,
+) -> Result<super::parakeet::ParakeetInferenceParams, Box<dyn std::error::Error>> {
+    if params.translate {
+        return Err(io_error("the parakeet engine does not support translation"));
+    }
+    Ok(super::parakeet::ParakeetInferenceParams {
+        timestamp_granularity: if params.word_timestamps {
+            super::parakeet::TimestampGranularity::Word
+        } else {
+            super::parakeet::TimestampGranularity::Segment
+        },
+        language: params.language.clone(),
+        vocabulary: Vocabulary::new(&params.vocabulary).terms().to_vec(),
+    })
+}
+
+#[cfg(feature = "whisperfile")]
+impl AnyEngine for super::whisperfile::WhisperfileEngine {
+    fn transcribe_samples(
+        &mut self,
+        samples: Vec<f32>,
+        params: &AnyInferenceParams,
+    ) -> Result<TranscriptionResult, Box<dyn std::error::Error>> {
+        let vocabulary = Vocabulary::new(&params.vocabulary);
+        let whisperfile_params = super::whisperfile::WhisperfileInferenceParams {
+            language: params.language.clone(),
+            translate: params.translate,
+            temperature: params.temperature,
+            prompt: params.prompt.clone(),
+            vocabulary: vocabulary.terms().to_vec(),
+            ..Default::default()
+        };
+        let result = crate::TranscriptionEngine::transcribe_samples(
+            self,
+            samples,
+            Some(whisperfile_params),
+        )?;
+        Ok(correct_vocabulary(result, &vocabulary, params))
+    }
+}
+
+#[cfg(feature = "remote")]
+impl AnyEngine for super::remote::RemoteEngine {
+    fn transcribe_samples(
+        &mut self,
+        samples: Vec<f32>,
+        params: &AnyInferenceParams,
+    ) -> Result<TranscriptionResult, Box<dyn std::error::Error>> {
+        use super::remote::TimestampGranularity;
+
+        if params.translate {
+            return Err(io_error("the remote engine does not support translation"));
+        }
+        let mut timestamp_granularities = vec![TimestampGranularity::Segment];
+        if params.word_timestamps {
+            timestamp_granularities.push(TimestampGranularity::Word);
+        }
+        let vocabulary = Vocabulary::new(&params.vocabulary);
+        let prompt = [params.prompt.clone(), vocabulary.prompt()]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>()
+            .join(" ");
+        let remote_params = super::remote::RemoteInferenceParams {
+            language: params.language.clone(),
+            prompt: (!prompt.is_empty()).then_some(prompt),
+            temperature: params.temperature,
+            timestamp_granularities,
+        };
+        let result =
+            crate::TranscriptionEngine::transcribe_samples(self, samples, Some(remote_params))?;
+        Ok(correct_vocabulary(result, &vocabulary, params))
+    }
+}
+
+fn io_error(message: impl Into<String>) -> Box<dyn std::error::Error> {
+    std::io::Error::other(message.into()).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{load_engine, EngineConfig};
+
+    #[test]
+    fn unknown_engine_lists_available_ones() {
+        let error = load_engine(&EngineConfig {
+            engine: "nope".to_string(),
+            ..Default::default()
+        })
+        .err()
+        .expect("unknown engine must fail");
+
+        assert!(error.to_string().contains("engine 'nope' is not available"));
+    }
+}
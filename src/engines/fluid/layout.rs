@@ -0,0 +1,629 @@
+//! Inspects a Fluid model directory the same way the Swift bridge resolves it, so a
+//! failed load can say which files are missing or damaged.
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+const ASR_MODELS: &[&str] = &[
+    "Preprocessor.mlmodelc",
+    "Encoder.mlmodelc",
+    "Decoder.mlmodelc",
+    "JointDecision.mlmodelc",
+];
+const ASR_VOCABULARY: &str = "parakeet_vocab.json";
+const CTC_MODELS: &[&str] = &["MelSpectrogram.mlmodelc", "AudioEncoder.mlmodelc"];
+const CTC_VOCABULARY: &str = "vocab.json";
+const TOKENIZER_FILES: &[&str] = &["tokenizer.json", "tokenizer_config.json"];
+const OPTIONAL_TOKENIZER_FILES: &[&str] = &["special_tokens_map.json", "config.json"];
+const DIARIZER_FOLDER: &str = "speaker-diarization-coreml";
+const DIARIZER_MODELS: &[&str] = &[
+    "Segmentation.mlmodelc",
+    "FBank.mlmodelc",
+    "Embedding.mlmodelc",
+    "PldaRho.mlmodelc",
+];
+const PLDA_PARAMETERS: &str = "plda-parameters.json";
+/// Every compiled Core ML bundle has one; without it the bundle cannot load.
+const COMPILED_MODEL_MARKER: &str = "coremldata.bin";
+
+/// Parakeet TDT 0.6b: v2 is English-only, v3 is multilingual.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum AsrVersion {
+    V2,
+    V3,
+}
+
+impl AsrVersion {
+    pub(crate) fn as_wire_value(self) -> &'static str {
+        match self {
+            Self::V2 => "v2",
+            Self::V3 => "v3",
+        }
+    }
+
+    /// Whether the model transcribes `language`, a code such as `en` or `de-AT`.
+    pub fn supports_language(self, language: &str) -> bool {
+        match self {
+            Self::V2 => {
+                let base = language.split(['-', '_']).next().unwrap_or(language);
+                base.trim().eq_ignore_ascii_case("en")
+            }
+            Self::V3 => true,
+        }
+    }
+
+    /// Fails when `language` needs a model other than this one.
+    pub(crate) fn check_language(
+        self,
+        language: Option<&str>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        match language.map(str::trim).filter(|language| !language.is_empty()) {
+            Some(language) if !self.supports_language(language) => Err(super::io_error(format!(
+                "language {language:?} needs the multilingual Parakeet v3 model; the loaded model is the English-only v2"
+            ))),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn folder_name(self) -> &'static str {
+        match self {
+            Self::V2 => "parakeet-tdt-0.6b-v2-coreml",
+            Self::V3 => "parakeet-tdt-0.6b-v3-coreml",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CtcVariant {
+    Ctc110m,
+    Ctc06b,
+}
+
+impl CtcVariant {
+    pub fn folder_name(self) -> &'static str {
+        match self {
+            Self::Ctc110m => "parakeet-ctc-110m-coreml",
+            Self::Ctc06b => "parakeet-ctc-0.6b-coreml",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FileStatus {
+    Present,
+    Missing,
+    /// The file exists but cannot be used, with the reason.
+    Corrupt(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileCheck {
+    pub path: PathBuf,
+    pub status: FileStatus,
+}
+
+impl FileCheck {
+    fn inspect(path: PathBuf) -> Self {
+        let status = file_status(&path);
+        Self { path, status }
+    }
+
+    pub fn is_present(&self) -> bool {
+        self.status == FileStatus::Present
+    }
+}
+
+/// A model directory and the files the bridge needs from it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ComponentCheck {
+    pub directory: PathBuf,
+    pub files: Vec<FileCheck>,
+}
+
+impl ComponentCheck {
+    fn inspect(directory: PathBuf, names: &[&str]) -> Self {
+        let files = names
+            .iter()
+            .map(|name| FileCheck::inspect(directory.join(name)))
+            .collect();
+        Self { directory, files }
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.files.iter().all(FileCheck::is_present)
+    }
+
+    /// Files that are missing or corrupt.
+    pub fn problems(&self) -> impl Iterator<Item = &FileCheck> {
+        self.files.iter().filter(|file| !file.is_present())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AsrCandidate {
+    pub version: AsrVersion,
+    pub check: ComponentCheck,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CtcCandidate {
+    pub variant: CtcVariant,
+    pub check: ComponentCheck,
+}
+
+/// What a Fluid model directory contains, as the bridge would see it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModelLayout {
+    /// Every ASR directory the bridge probes, in its order (v3 before v2).
+    pub asr_candidates: Vec<AsrCandidate>,
+    /// The CTC model used for vocabulary boosting: the first complete candidate, or
+    /// else the first one whose directory exists. It is optional.
+    pub ctc: Option<CtcCandidate>,
+    /// Tokenizer files next to the CTC model, checked only when there is one.
+    pub tokenizer: Option<ComponentCheck>,
+    /// Diarization models, when a diarization directory was given.
+    pub diarization: Option<ComponentCheck>,
+}
+
+impl ModelLayout {
+    pub fn inspect(asr_model_dir: &Path, diarization_model_dir: Option<&Path>) -> Self {
+        let asr_candidates = asr_directories(asr_model_dir)
+            .into_iter()
+            .map(|(directory, version)| AsrCandidate {
+                version,
+                check: ComponentCheck::inspect(directory, &asr_file_names()),
+            })
+            .collect::<Vec<_>>();
+
+        let ctc_near = asr_candidates
+            .iter()
+            .find(|candidate| candidate.check.is_complete())
+            .or_else(|| asr_candidates.first())
+            .map(|candidate| candidate.check.directory.clone());
+        let ctc = ctc_near.as_deref().and_then(inspect_ctc);
+        let tokenizer = ctc.as_ref().map(|ctc| {
+            let mut check = ComponentCheck::inspect(ctc.check.directory.clone(), TOKENIZER_FILES);
+            check.files.extend(
+                OPTIONAL_TOKENIZER_FILES
+                    .iter()
+                    .map(|name| FileCheck::inspect(ctc.check.directory.join(name)))
+                    .filter(|file| file.status != FileStatus::Missing),
+            );
+            check
+        });
+
+        Self {
+            asr_candidates,
+            ctc,
+            tokenizer,
+            diarization: diarization_model_dir.map(inspect_diarization),
+        }
+    }
+
+    /// The ASR directory the bridge would load, if any candidate is complete.
+    pub fn asr(&self) -> Option<&AsrCandidate> {
+        self.asr_candidates
+            .iter()
+            .find(|candidate| candidate.check.is_complete())
+    }
+
+    pub fn asr_version(&self) -> Option<AsrVersion> {
+        self.asr().map(|candidate| candidate.version)
+    }
+
+    pub fn vocabulary_boosting_available(&self) -> bool {
+        self.ctc.as_ref().is_some_and(|ctc| ctc.check.is_complete())
+            && self
+                .tokenizer
+                .as_ref()
+                .is_some_and(ComponentCheck::is_complete)
+    }
+
+    /// Fails when the ASR models or requested diarization models are unusable, listing
+    /// the files at fault. A missing CTC model or tokenizer only disables boosting.
+    pub fn validate(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let mut problems = Vec::new();
+        if self.asr().is_none() {
+            // Candidates without a directory only add noise next to a partial download.
+            let existing = self
+                .asr_candidates
+                .iter()
+                .filter(|candidate| candidate.check.directory.is_dir())
+                .collect::<Vec<_>>();
+            if existing.is_empty() {
+                let expected = self
+                    .asr_candidates
+                    .iter()
+                    .map(|candidate| candidate.check.directory.display().to_string())
+                    .collect::<Vec<_>>();
+                problems.push(format!(
+                    "no ASR model directory; expected one of: {}",
+                    expected.join(", ")
+                ));
+            }
+            for candidate in existing {
+                problems.push(format!(
+                    "incomplete {:?} ASR models: {}",
+                    candidate.version,
+                    describe_problems(&candidate.check)
+                ));
+            }
+        }
+        if let Some(diarization) = self
+            .diarization
+            .as_ref()
+            .filter(|check| !check.is_complete())
+        {
+            problems.push(format!(
+                "incomplete diarization models: {}",
+                describe_problems(diarization)
+            ));
+        }
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(super::io_error(problems.join("; ")))
+        }
+    }
+}
+
+impl fmt::Display for ModelLayout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.asr() {
+            Some(candidate) => writeln!(
+                f,
+                "ASR: {:?} at {}",
+                candidate.version,
+                candidate.check.directory.display()
+            )?,
+            None => writeln!(f, "ASR: not found")?,
+        }
+        match &self.ctc {
+            Some(ctc) if ctc.check.is_complete() => writeln!(
+                f,
+                "CTC: {} at {}",
+                ctc.variant.folder_name(),
+                ctc.check.directory.display()
+            )?,
+            Some(ctc) => writeln!(f, "CTC: incomplete, {}", describe_problems(&ctc.check))?,
+            None => writeln!(f, "CTC: not found (vocabulary boosting disabled)")?,
+        }
+        match &self.tokenizer {
+            Some(tokenizer) if tokenizer.is_complete() => writeln!(f, "Tokenizer: present")?,
+            Some(tokenizer) => writeln!(f, "Tokenizer: {}", describe_problems(tokenizer))?,
+            None => writeln!(f, "Tokenizer: not checked")?,
+        }
+        match &self.diarization {
+            Some(diarization) if diarization.is_complete() => {
+                writeln!(f, "Diarization: {}", diarization.directory.display())
+            }
+            Some(diarization) => writeln!(f, "Diarization: {}", describe_problems(diarization)),
+            None => writeln!(f, "Diarization: not requested"),
+        }
+    }
+}
+
+fn asr_file_names() -> Vec<&'static str> {
+    let mut names = ASR_MODELS.to_vec();
+    names.push(ASR_VOCABULARY);
+    names
+}
+
+/// The directories `resolveAsrDirectory` probes, without duplicates.
+fn asr_directories(root: &Path) -> Vec<(PathBuf, AsrVersion)> {
+    let mut directories: Vec<(PathBuf, AsrVersion)> = Vec::new();
+    let candidates = [
+        (root.to_path_buf(), AsrVersion::V3),
+        (root.to_path_buf(), AsrVersion::V2),
+        (root.join(AsrVersion::V3.folder_name()), AsrVersion::V3),
+        (root.join(AsrVersion::V2.folder_name()), AsrVersion::V2),
+    ];
+    for (directory, version) in candidates {
+        let directory = repository_directory(&directory, version.folder_name());
+        if !directories.iter().any(|(seen, _)| *seen == directory) {
+            directories.push((directory, version));
+        }
+    }
+    directories
+}
+
+fn repository_directory(directory: &Path, folder_name: &str) -> PathBuf {
+    if directory
+        .file_name()
+        .is_some_and(|name| name == folder_name)
+    {
+        directory.to_path_buf()
+    } else {
+        directory.join(folder_name)
+    }
+}
+
+/// Mirrors `resolveCtcDirectory`.
+fn inspect_ctc(asr_directory: &Path) -> Option<CtcCandidate> {
+    let parent = asr_directory.parent().unwrap_or(asr_directory);
+    let candidates = [
+        (
+            parent.join(CtcVariant::Ctc110m.folder_name()),
+            CtcVariant::Ctc110m,
+        ),
+        (
+            parent.join(CtcVariant::Ctc06b.folder_name()),
+            CtcVariant::Ctc06b,
+        ),
+        (asr_directory.join("ctc"), CtcVariant::Ctc110m),
+        (asr_directory.to_path_buf(), CtcVariant::Ctc110m),
+    ];
+    let mut names = CTC_MODELS.to_vec();
+    names.push(CTC_VOCABULARY);
+
+    let mut first_existing = None;
+    for (directory, variant) in candidates {
+        if !directory.is_dir() {
+            continue;
+        }
+        let check = ComponentCheck::inspect(directory, &names);
+        if check.is_complete() {
+            return Some(CtcCandidate { variant, check });
+        }
+        // The ASR directory itself rarely holds CTC models, so it is no useful hint.
+        if first_existing.is_none() && check.directory != asr_directory {
+            first_existing = Some(CtcCandidate { variant, check });
+        }
+    }
+    first_existing
+}
+
+/// Mirrors `resolveOfflineDiarizationBaseDirectory`.
+fn inspect_diarization(provided: &Path) -> ComponentCheck {
+    let (base, repository) = if provided
+        .file_name()
+        .is_some_and(|name| name == DIARIZER_FOLDER)
+    {
+        (
+            provided.parent().unwrap_or(provided).to_path_buf(),
+            provided.to_path_buf(),
+        )
+    } else {
+        (provided.to_path_buf(), provided.join(DIARIZER_FOLDER))
+    };
+
+    let mut check = ComponentCheck::inspect(repository, DIARIZER_MODELS);
+    let plda = [
+        base.join(PLDA_PARAMETERS),
+        base.join(DIARIZER_FOLDER).join(PLDA_PARAMETERS),
+        base.join("speaker-diarization-offline")
+            .join(PLDA_PARAMETERS),
+    ]
+    .into_iter()
+    .map(FileCheck::inspect)
+    .find(|file| file.status != FileStatus::Missing)
+    .unwrap_or_else(|| FileCheck {
+        path: base.join(PLDA_PARAMETERS),
+        status: FileStatus::Missing,
+    });
+    check.files.push(plda);
+    check
+}
+
+fn file_status(path: &Path) -> FileStatus {
+    if !path.exists() {
+        return FileStatus::Missing;
+    }
+    let is_compiled_model = path
+        .extension()
+        .is_some_and(|extension| extension == "mlmodelc");
+    if is_compiled_model {
+        if !path.is_dir() {
+            return FileStatus::Corrupt("not a compiled Core ML directory".to_string());
+        }
+        if !path.join(COMPILED_MODEL_MARKER).is_file() {
+            return FileStatus::Corrupt(format!("{COMPILED_MODEL_MARKER} is missing"));
+        }
+        return FileStatus::Present;
+    }
+
+    match std::fs::read(path) {
+        Ok(bytes) if bytes.is_empty() => FileStatus::Corrupt("empty file".to_string()),
+        Ok(bytes)
+            if path
+                .extension()
+                .is_some_and(|extension| extension == "json") =>
+        {
+            match serde_json::from_slice::<serde_json::Value>(&bytes) {
+                Ok(_) => FileStatus::Present,
+                Err(error) => FileStatus::Corrupt(format!("invalid JSON: {error}")),
+            }
+        }
+        Ok(_) => FileStatus::Present,
+        Err(error) => FileStatus::Corrupt(error.to_string()),
+    }
+}
+
+fn describe_problems(check: &ComponentCheck) -> String {
+    check
+        .problems()
+        .map(|file| match &file.status {
+            FileStatus::Corrupt(reason) => format!("{} is corrupt ({reason})", file.path.display()),
+            _ => format!("{} is missing", file.path.display()),
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::{Path, PathBuf};
+
+    use super::{
+        AsrVersion, CtcVariant, FileStatus, ModelLayout, ASR_MODELS, ASR_VOCABULARY, CTC_MODELS,
+        CTC_VOCABULARY, DIARIZER_MODELS, PLDA_PARAMETERS, TOKENIZER_FILES,
+    };
+
+    struct TempTree(PathBuf);
+
+    impl TempTree {
+        fn new(name: &str) -> Self {
+            let root = std::env::temp_dir().join(format!(
+                "glimpse-fluid-layout-{name}-{}",
+                std::process::id()
+            ));
+            let _ = fs::remove_dir_all(&root);
+            fs::create_dir_all(&root).unwrap();
+            Self(root)
+        }
+    }
+
+    impl Drop for TempTree {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn compiled_models(directory: &Path, names: &[&str]) {
+        for name in names {
+            let bundle = directory.join(name);
+            fs::create_dir_all(&bundle).unwrap();
+            fs::write(bundle.join("coremldata.bin"), b"model").unwrap();
+        }
+    }
+
+    fn json(path: &Path) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, b"{}").unwrap();
+    }
+
+    fn asr_models(directory: &Path) {
+        compiled_models(directory, ASR_MODELS);
+        json(&directory.join(ASR_VOCABULARY));
+    }
+
+    #[test]
+    fn finds_v2_models_when_v3_is_absent() {
+        let tree = TempTree::new("v2");
+        let v2 = tree.0.join(AsrVersion::V2.folder_name());
+        asr_models(&v2);
+
+        let layout = ModelLayout::inspect(&tree.0, None);
+
+        assert_eq!(layout.asr_version(), Some(AsrVersion::V2));
+        assert_eq!(layout.asr().unwrap().check.directory, v2);
+        assert!(layout.ctc.is_none());
+        assert!(!layout.vocabulary_boosting_available());
+        assert!(layout.validate().is_ok());
+    }
+
+    #[test]
+    fn v2_only_accepts_english() {
+        assert!(AsrVersion::V2.check_language(Some("en-US")).is_ok());
+        assert!(AsrVersion::V2.check_language(None).is_ok());
+        assert!(AsrVersion::V3.check_language(Some("de")).is_ok());
+
+        let message = AsrVersion::V2
+            .check_language(Some("de"))
+            .unwrap_err()
+            .to_string();
+        assert!(message.contains("v3"), "{message}");
+    }
+
+    #[test]
+    fn accepts_the_version_directory_itself() {
+        let tree = TempTree::new("direct");
+        let v3 = tree.0.join(AsrVersion::V3.folder_name());
+        asr_models(&v3);
+
+        let layout = ModelLayout::inspect(&v3, None);
+
+        assert_eq!(layout.asr_version(), Some(AsrVersion::V3));
+        assert_eq!(layout.asr_candidates[0].check.directory, v3);
+    }
+
+    #[test]
+    fn reports_missing_and_corrupt_asr_files() {
+        let tree = TempTree::new("broken");
+        let v3 = tree.0.join(AsrVersion::V3.folder_name());
+        compiled_models(&v3, &ASR_MODELS[..2]);
+        fs::write(v3.join(ASR_MODELS[2]), b"not a bundle").unwrap();
+        fs::write(v3.join(ASR_VOCABULARY), b"{\"truncated\":").unwrap();
+
+        let layout = ModelLayout::inspect(&tree.0, None);
+        let candidate = &layout.asr_candidates[0];
+
+        assert_eq!(layout.asr_version(), None);
+        assert!(matches!(
+            candidate.check.files[2].status,
+            FileStatus::Corrupt(_)
+        ));
+        assert_eq!(candidate.check.files[3].status, FileStatus::Missing);
+        assert!(matches!(
+            candidate.check.files[4].status,
+            FileStatus::Corrupt(ref reason) if reason.starts_with("invalid JSON")
+        ));
+        let message = layout.validate().unwrap_err().to_string();
+        assert!(message.contains("incomplete V3 ASR models"), "{message}");
+        assert!(
+            message.contains("JointDecision.mlmodelc is missing"),
+            "{message}"
+        );
+        assert!(!message.contains("V2"), "{message}");
+    }
+
+    #[test]
+    fn lists_expected_directories_when_nothing_is_there() {
+        let tree = TempTree::new("empty");
+
+        let message = ModelLayout::inspect(&tree.0, None)
+            .validate()
+            .unwrap_err()
+            .to_string();
+
+        assert!(message.contains(AsrVersion::V3.folder_name()), "{message}");
+        assert!(message.contains(AsrVersion::V2.folder_name()), "{message}");
+    }
+
+    #[test]
+    fn finds_ctc_model_and_tokenizer_next_to_asr() {
+        let tree = TempTree::new("ctc");
+        asr_models(&tree.0.join(AsrVersion::V3.folder_name()));
+        let ctc = tree.0.join(CtcVariant::Ctc06b.folder_name());
+        compiled_models(&ctc, CTC_MODELS);
+        json(&ctc.join(CTC_VOCABULARY));
+        json(&ctc.join(TOKENIZER_FILES[0]));
+
+        let layout = ModelLayout::inspect(&tree.0, None);
+
+        assert_eq!(layout.ctc.as_ref().unwrap().variant, CtcVariant::Ctc06b);
+        assert!(!layout.vocabulary_boosting_available());
+        let tokenizer = layout.tokenizer.as_ref().unwrap();
+        assert_eq!(
+            tokenizer
+                .problems()
+                .map(|file| &file.path)
+                .collect::<Vec<_>>(),
+            [&ctc.join(TOKENIZER_FILES[1])]
+        );
+
+        json(&ctc.join(TOKENIZER_FILES[1]));
+        assert!(ModelLayout::inspect(&tree.0, None).vocabulary_boosting_available());
+    }
+
+    #[test]
+    fn checks_diarization_models_and_plda_parameters() {
+        let tree = TempTree::new("diarization");
+        asr_models(&tree.0.join(AsrVersion::V3.folder_name()));
+        let diarization = tree.0.join("diarization");
+        let repository = diarization.join("speaker-diarization-coreml");
+        compiled_models(&repository, DIARIZER_MODELS);
+
+        let layout = ModelLayout::inspect(&tree.0, Some(&diarization));
+        let message = layout.validate().unwrap_err().to_string();
+        assert!(message.contains(PLDA_PARAMETERS), "{message}");
+
+        json(&repository.join(PLDA_PARAMETERS));
+        let layout = ModelLayout::inspect(&tree.0, Some(&repository));
+        assert!(layout.validate().is_ok());
+        assert!(layout.to_string().contains("Diarization: "));
+    }
+}
@@ -0,0 +1,1152 @@
+// The bridge is only reached on macOS; elsewhere loading fails before it is used.
+#![cfg_attr(not(target_os = "macos"), allow(dead_code))]
+
+use std::ffi::{c_char, c_void, CStr, CString};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use libloading::{Library, Symbol};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::vocabulary::Vocabulary;
+use crate::{
+    SpeakerTurn, TranscriptionEngine, TranscriptionResult, TranscriptionSegment,
+    TranscriptionToken, TranscriptionWord, Warning,
+};
+
+mod layout;
+
+pub use layout::{
+    AsrCandidate, AsrVersion, ComponentCheck, CtcCandidate, CtcVariant, FileCheck, FileStatus,
+    ModelLayout,
+};
+
+const BRIDGE_SCHEMA_VERSION: u32 = 1;
+static TEMP_WAV_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FluidTimestampGranularity {
+    /// Segments and word timings.
+    #[default]
+    WordPreferred,
+    SegmentsOnly,
+    /// Segments, word timings and the sub-word token timings they were merged from.
+    Tokens,
+}
+
+impl FluidTimestampGranularity {
+    fn as_wire_value(self) -> &'static str {
+        match self {
+            Self::WordPreferred => "word_preferred",
+            Self::SegmentsOnly => "segments_only",
+            Self::Tokens => "tokens",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FluidModelParams {
+    pub diarization_model_dir: Option<PathBuf>,
+    pub dylib_path: Option<PathBuf>,
+    pub runtime_macos_major: Option<u32>,
+    /// Lets the bridge download missing tokenizer files for vocabulary boosting from
+    /// huggingface.co. When off, such transcriptions fail with `network_disabled`.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub allow_network: bool,
+    /// Loads only this Parakeet version; `None` takes v3 if present, else v2.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub model_version: Option<AsrVersion>,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FluidInferenceParams {
+    pub language: Option<String>,
+    pub vocabulary: Vec<String>,
+    pub timestamp_granularity: FluidTimestampGranularity,
+}
+
+impl Default for FluidInferenceParams {
+    fn default() -> Self {
+        Self {
+            language: None,
+            vocabulary: Vec::new(),
+            timestamp_granularity: FluidTimestampGranularity::WordPreferred,
+        }
+    }
+}
+
+/// An error reported by the Swift bridge, such as `model_not_found` or
+/// `unsupported_platform`, with the bridge's message.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct FluidBridgeError {
+    pub code: String,
+    pub message: String,
+}
+
+impl fmt::Display for FluidBridgeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.code, self.message)
+    }
+}
+
+impl std::error::Error for FluidBridgeError {}
+
+pub struct FluidEngine {
+    loaded_model_path: Option<PathBuf>,
+    bridge: Option<FluidBridge>,
+}
+
+impl Default for FluidEngine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FluidEngine {
+    pub fn new() -> Self {
+        Self {
+            loaded_model_path: None,
+            bridge: None,
+        }
+    }
+}
+
+impl FluidEngine {
+    /// Speaker turns for a WAV file. Needs `FluidModelParams::diarization_model_dir`.
+    pub fn diarize_file(
+        &mut self,
+        wav_path: &Path,
+        speaker_count: Option<u32>,
+    ) -> Result<Vec<SpeakerTurn>, Box<dyn std::error::Error>> {
+        let bridge = self
+            .bridge
+            .as_ref()
+            .ok_or_else(|| io_error("Model not loaded. Call load_model() first."))?;
+
+        if !wav_path.exists() {
+            return Err(io_error(format!(
+                "Audio file not found: {}",
+                wav_path.display()
+            )));
+        }
+
+        bridge.diarize(wav_path, speaker_count)
+    }
+}
+
+impl Drop for FluidEngine {
+    fn drop(&mut self) {
+        self.unload_model();
+    }
+}
+
+impl TranscriptionEngine for FluidEngine {
+    type InferenceParams = FluidInferenceParams;
+    type ModelParams = FluidModelParams;
+
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            level = "info",
+            skip_all,
+            fields(engine = "fluid", model = %model_path.display()),
+            err
+        )
+    )]
+    fn load_model_with_params(
+        &mut self,
+        model_path: &Path,
+        params: Self::ModelParams,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        #[cfg(not(target_os = "macos"))]
+        {
+            let _ = (model_path, params);
+            Err(io_error("Fluid engine is only supported on macOS"))
+        }
+
+        #[cfg(target_os = "macos")]
+        {
+            if !model_path.exists() {
+                return Err(io_error(format!(
+                    "Model directory not found: {}",
+                    model_path.display()
+                )));
+            }
+
+            let runtime_macos_major = params
+                .runtime_macos_major
+                .or_else(detect_macos_major)
+                .ok_or_else(|| io_error("failed to determine macOS version"))?;
+
+            if runtime_macos_major < 14 {
+                return Err(io_error(format!(
+                    "Fluid engine requires macOS 14+, found macOS {runtime_macos_major}"
+                )));
+            }
+
+            let bridge = FluidBridge::new(
+                model_path.to_path_buf(),
+                params.diarization_model_dir,
+                runtime_macos_major,
+                params.allow_network,
+                params.model_version,
+                params.dylib_path.as_deref(),
+            )?;
+
+            self.loaded_model_path = Some(model_path.to_path_buf());
+            self.bridge = Some(bridge);
+            Ok(())
+        }
+    }
+
+    fn unload_model(&mut self) {
+        self.loaded_model_path = None;
+        self.bridge = None;
+    }
+
+    fn transcribe_samples(
+        &mut self,
+        samples: Vec<f32>,
+        params: Option<Self::InferenceParams>,
+    ) -> Result<TranscriptionResult, Box<dyn std::error::Error>> {
+        if self.bridge.is_none() {
+            return Err(io_error("Model not loaded. Call load_model() first."));
+        }
+
+        let temp_wav = TempWav::from_f32_samples_16khz(&samples)?;
+        self.transcribe_file(temp_wav.path(), params)
+    }
+
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            level = "debug",
+            skip_all,
+            fields(engine = "fluid", wav = %wav_path.display()),
+            err
+        )
+    )]
+    fn transcribe_file(
+        &mut self,
+        wav_path: &Path,
+        params: Option<Self::InferenceParams>,
+    ) -> Result<TranscriptionResult, Box<dyn std::error::Error>> {
+        let bridge = self
+            .bridge
+            .as_ref()
+            .ok_or_else(|| io_error("Model not loaded. Call load_model() first."))?;
+
+        if !wav_path.exists() {
+            return Err(io_error(format!(
+                "Audio file not found: {}",
+                wav_path.display()
+            )));
+        }
+
+        let params = params.unwrap_or_default();
+        bridge.transcribe(wav_path, &params)
+    }
+}
+
+struct TempWav {
+    path: PathBuf,
+}
+
+impl TempWav {
+    fn from_f32_samples_16khz(samples: &[f32]) -> Result<Self, Box<dyn std::error::Error>> {
+        let mut path = std::env::temp_dir();
+        path.push(unique_temp_wav_name());
+
+        let spec = hound::WavSpec {
+            channels: 1,
+            sample_rate: 16_000,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+
+        let mut writer = hound::WavWriter::create(&path, spec)?;
+        for sample in samples {
+            let clamped = sample.clamp(-1.0, 1.0);
+            let pcm = (clamped * i16::MAX as f32).round() as i16;
+            writer.write_sample(pcm)?;
+        }
+        writer.finalize()?;
+
+        Ok(Self { path })
+    }
+
+    fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for TempWav {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+fn unique_temp_wav_name() -> String {
+    let counter = TEMP_WAV_COUNTER.fetch_add(1, Ordering::Relaxed);
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_nanos())
+        .unwrap_or(0);
+    format!("glimpse-speech-fluid-{nanos}-{counter}.wav")
+}
+
+struct FluidBridge {
+    library: Arc<FluidBridgeLibrary>,
+    handle: Mutex<usize>,
+    /// The loaded Parakeet version, requested or found the way the bridge probes.
+    model_version: Option<AsrVersion>,
+}
+
+impl FluidBridge {
+    fn new(
+        asr_model_dir: PathBuf,
+        diarization_model_dir: Option<PathBuf>,
+        runtime_macos_major: u32,
+        allow_network: bool,
+        model_version: Option<AsrVersion>,
+        explicit_dylib_path: Option<&Path>,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        let library = Arc::new(FluidBridgeLibrary::load(explicit_dylib_path)?);
+
+        let payload = BridgeConfigPayload {
+            schema_version: BRIDGE_SCHEMA_VERSION,
+            asr_model_dir: asr_model_dir.display().to_string(),
+            diarization_model_dir: diarization_model_dir
+                .as_ref()
+                .map(|path| path.display().to_string()),
+            runtime_macos_major,
+            allow_network,
+            model_version: model_version.map(AsrVersion::as_wire_value),
+        };
+
+        let payload_bytes = serde_json::to_vec(&payload)?;
+        let payload_len = isize::try_from(payload_bytes.len())
+            .map_err(|_| io_error("Fluid create payload is too large"))?;
+
+        let layout = || ModelLayout::inspect(&asr_model_dir, diarization_model_dir.as_deref());
+        let handle = match library.create_with_error {
+            Some(create_with_error) => {
+                let mut error_ptr = std::ptr::null_mut();
+                let mut error_len: isize = 0;
+                // SAFETY: function pointer comes from the loaded Fluid bridge dylib, and the
+                // out-pointers are valid for the duration of the call.
+                let handle = unsafe {
+                    create_with_error(
+                        payload_bytes.as_ptr(),
+                        payload_len,
+                        &mut error_ptr,
+                        &mut error_len,
+                    )
+                };
+                if handle.is_null() {
+                    let bytes = library.take_buffer(error_ptr, error_len)?;
+                    let mut error = match parse_bridge_payload::<Value>(&bytes, "create") {
+                        Err(error) => error,
+                        Ok(_) => return Err(io_error("Fluid bridge failed to initialize")),
+                    };
+                    if let Some(bridge_error) = error.downcast_mut::<FluidBridgeError>() {
+                        if bridge_error.code == "model_not_found" {
+                            if let Err(problem) = layout().validate() {
+                                bridge_error.message =
+                                    format!("{} ({problem})", bridge_error.message);
+                            }
+                        }
+                    }
+                    return Err(error);
+                }
+                handle
+            }
+            // Bridges built before `glimpse_fluid_create_with_error` only return null.
+            None => {
+                #[cfg(feature = "tracing")]
+                tracing::debug!(
+                    "bridge has no glimpse_fluid_create_with_error; using glimpse_fluid_create"
+                );
+                // SAFETY: function pointer comes from the loaded Fluid bridge dylib.
+                let handle = unsafe { (library.create)(payload_bytes.as_ptr(), payload_len) };
+                if handle.is_null() {
+                    return Err(match layout().validate() {
+                        Err(problem) => {
+                            io_error(format!("Fluid bridge failed to initialize: {problem}"))
+                        }
+                        Ok(()) => io_error("Fluid bridge failed to initialize"),
+                    });
+                }
+                handle
+            }
+        };
+
+        Ok(Self {
+            library,
+            handle: Mutex::new(handle as usize),
+            model_version: model_version.or_else(|| layout().asr_version()),
+        })
+    }
+
+    fn active_handle(&self) -> Result<*mut c_void, Box<dyn std::error::Error>> {
+        let guard = self
+            .handle
+            .lock()
+            .map_err(|_| io_error("failed to lock Fluid bridge handle"))?;
+        if *guard == 0 {
+            return Err(io_error("Fluid bridge handle is unavailable"));
+        }
+
+        Ok(*guard as *mut c_void)
+    }
+
+    fn transcribe(
+        &self,
+        wav_path: &Path,
+        params: &FluidInferenceParams,
+    ) -> Result<TranscriptionResult, Box<dyn std::error::Error>> {
+        let language_hint = normalize_language_hint(params.language.as_deref());
+        if let Some(version) = self.model_version {
+            version.check_language(language_hint.as_deref())?;
+        }
+        let payload = BridgeTranscribePayload {
+            schema_version: BRIDGE_SCHEMA_VERSION,
+            language_hint: language_hint.clone(),
+            vocabulary: Vocabulary::new(&params.vocabulary).terms().to_vec(),
+            timestamps: params.timestamp_granularity.as_wire_value(),
+        };
+
+        let payload_bytes = serde_json::to_vec(&payload)?;
+        let payload_len = isize::try_from(payload_bytes.len())
+            .map_err(|_| io_error("Fluid transcribe payload is too large"))?;
+
+        let wav_path_c = CString::new(wav_path.display().to_string())
+            .map_err(|_| io_error("wav path contains interior null bytes"))?;
+        let handle = self.active_handle()?;
+
+        let mut out_len: isize = 0;
+        // SAFETY: all pointers and lengths are valid for the duration of the call.
+        let out_ptr = unsafe {
+            (self.library.transcribe_wav)(
+                handle,
+                wav_path_c.as_ptr(),
+                payload_bytes.as_ptr(),
+                payload_len,
+                &mut out_len,
+            )
+        };
+
+        let bytes = self.library.take_buffer(out_ptr, out_len)?;
+        let payload: BridgeTranscriptPayload = parse_bridge_payload(&bytes, "transcribe")?;
+        let mut result = payload.into_transcription_result();
+        // The bridge passes no language to FluidAudio, which detects it by itself.
+        if let Some(language) = language_hint {
+            result.warnings.push(Warning::LanguageHintIgnored(language));
+        }
+        Ok(result)
+    }
+
+    fn diarize(
+        &self,
+        wav_path: &Path,
+        speaker_count: Option<u32>,
+    ) -> Result<Vec<SpeakerTurn>, Box<dyn std::error::Error>> {
+        let payload = BridgeDiarizePayload {
+            schema_version: BRIDGE_SCHEMA_VERSION,
+            speaker_count,
+        };
+
+        let payload_bytes = serde_json::to_vec(&payload)?;
+        let payload_len = isize::try_from(payload_bytes.len())
+            .map_err(|_| io_error("Fluid diarize payload is too large"))?;
+
+        let wav_path_c = CString::new(wav_path.display().to_string())
+            .map_err(|_| io_error("wav path contains interior null bytes"))?;
+        let handle = self.active_handle()?;
+
+        let mut out_len: isize = 0;
+        // SAFETY: all pointers and lengths are valid for the duration of the call.
+        let out_ptr = unsafe {
+            (self.library.diarize_wav)(
+                handle,
+                wav_path_c.as_ptr(),
+                payload_bytes.as_ptr(),
+                payload_len,
+                &mut out_len,
+            )
+        };
+
+        let bytes = self.library.take_buffer(out_ptr, out_len)?;
+        let payload: BridgeDiarizationPayload = parse_bridge_payload(&bytes, "diarize")?;
+        Ok(payload.into_speaker_turns())
+    }
+}
+
+impl Drop for FluidBridge {
+    fn drop(&mut self) {
+        let Ok(mut guard) = self.handle.lock() else {
+            return;
+        };
+        if *guard == 0 {
+            return;
+        }
+
+        let handle = *guard as *mut c_void;
+        // SAFETY: handle was created by the same bridge dylib.
+        unsafe {
+            (self.library.destroy)(handle);
+        }
+        *guard = 0;
+    }
+}
+
+type GlimpseFluidCreateFn = unsafe extern "C" fn(*const u8, isize) -> *mut c_void;
+type GlimpseFluidCreateWithErrorFn =
+    unsafe extern "C" fn(*const u8, isize, *mut *mut u8, *mut isize) -> *mut c_void;
+type GlimpseFluidLogFn = extern "C" fn(i32, *const c_char, *const c_char);
+type GlimpseFluidSetLogCallbackFn = unsafe extern "C" fn(Option<GlimpseFluidLogFn>);
+type GlimpseFluidDestroyFn = unsafe extern "C" fn(*mut c_void);
+type GlimpseFluidTranscribeFn =
+    unsafe extern "C" fn(*mut c_void, *const i8, *const u8, isize, *mut isize) -> *mut u8;
+type GlimpseFluidDiarizeFn =
+    unsafe extern "C" fn(*mut c_void, *const i8, *const u8, isize, *mut isize) -> *mut u8;
+type GlimpseFluidFreeBufferFn = unsafe extern "C" fn(*mut u8, isize);
+
+struct FluidBridgeLibrary {
+    _library: Library,
+    create: GlimpseFluidCreateFn,
+    create_with_error: Option<GlimpseFluidCreateWithErrorFn>,
+    destroy: GlimpseFluidDestroyFn,
+    transcribe_wav: GlimpseFluidTranscribeFn,
+    diarize_wav: GlimpseFluidDiarizeFn,
+    free_buffer: GlimpseFluidFreeBufferFn,
+}
+
+impl FluidBridgeLibrary {
+    fn load(explicit_path: Option<&Path>) -> Result<Self, Box<dyn std::error::Error>> {
+        let dylib_path = resolve_bridge_dylib_path(explicit_path)?;
+
+        // SAFETY: loading shared library by filesystem path.
+        let library = unsafe { Library::new(&dylib_path) }.map_err(|error| {
+            io_error(format!(
+                "failed to load Fluid bridge dylib {}: {error}",
+                dylib_path.display()
+            ))
+        })?;
+
+        let create = load_symbol::<GlimpseFluidCreateFn>(&library, b"glimpse_fluid_create\0")?;
+        let create_with_error = load_optional_symbol::<GlimpseFluidCreateWithErrorFn>(
+            &library,
+            b"glimpse_fluid_create_with_error\0",
+        );
+        // Older bridges have no log callback and keep logging to NSLog.
+        if let Some(set_log_callback) = load_optional_symbol::<GlimpseFluidSetLogCallbackFn>(
+            &library,
+            b"glimpse_fluid_set_log_callback\0",
+        ) {
+            // SAFETY: function pointer comes from the loaded Fluid bridge dylib, and the
+            // callback is a plain function that lives as long as the process.
+            unsafe { set_log_callback(Some(forward_bridge_log)) };
+        }
+        let destroy = load_symbol::<GlimpseFluidDestroyFn>(&library, b"glimpse_fluid_destroy\0")?;
+        let transcribe_wav =
+            load_symbol::<GlimpseFluidTranscribeFn>(&library, b"glimpse_fluid_transcribe_wav\0")?;
+        let diarize_wav =
+            load_symbol::<GlimpseFluidDiarizeFn>(&library, b"glimpse_fluid_diarize_wav\0")?;
+        let free_buffer =
+            load_symbol::<GlimpseFluidFreeBufferFn>(&library, b"glimpse_fluid_free_buffer\0")?;
+
+        Ok(Self {
+            _library: library,
+            create,
+            create_with_error,
+            destroy,
+            transcribe_wav,
+            diarize_wav,
+            free_buffer,
+        })
+    }
+
+    fn take_buffer(&self, ptr: *mut u8, len: isize) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+        if ptr.is_null() || len <= 0 {
+            return Err(io_error("Fluid bridge returned an empty response"));
+        }
+
+        let len_isize = len;
+        let len =
+            usize::try_from(len_isize).map_err(|_| io_error("invalid Fluid response length"))?;
+
+        // SAFETY: `ptr` points to `len` bytes returned by bridge API.
+        let bytes = unsafe { std::slice::from_raw_parts(ptr, len) }.to_vec();
+        // SAFETY: buffer ownership is returned to bridge API here.
+        unsafe {
+            (self.free_buffer)(ptr, len_isize);
+        }
+
+        Ok(bytes)
+    }
+}
+
+fn load_symbol<T>(library: &Library, symbol: &[u8]) -> Result<T, Box<dyn std::error::Error>>
+where
+    T: Copy,
+{
+    // SAFETY: symbol lookup in a loaded library.
+    let value: Symbol<'_, T> = unsafe { library.get(symbol) }.map_err(|error| {
+        io_error(format!(
+            "missing Fluid bridge symbol {}: {error}",
+            String::from_utf8_lossy(symbol)
+        ))
+    })?;
+
+    Ok(*value)
+}
+
+/// Forwards a bridge log record to the `log` crate, under the target
+/// `glimpse_speech::fluid::bridge::<category>`. Levels are numbered like [`log::Level`].
+extern "C" fn forward_bridge_log(level: i32, category: *const c_char, message: *const c_char) {
+    let level = match level {
+        1 => log::Level::Error,
+        2 => log::Level::Warn,
+        3 => log::Level::Info,
+        4 => log::Level::Debug,
+        _ => log::Level::Trace,
+    };
+    let text = |pointer: *const c_char| {
+        if pointer.is_null() {
+            return String::new();
+        }
+        // SAFETY: the bridge passes NUL-terminated strings valid for this call.
+        unsafe { CStr::from_ptr(pointer) }
+            .to_string_lossy()
+            .into_owned()
+    };
+    let category = text(category);
+    let target = if category.is_empty() {
+        "glimpse_speech::fluid::bridge".to_string()
+    } else {
+        format!("glimpse_speech::fluid::bridge::{category}")
+    };
+    log::log!(target: &target, level, "{}", text(message));
+}
+
+/// Like [`load_symbol`], for symbols that older bridge builds do not export.
+fn load_optional_symbol<T>(library: &Library, symbol: &[u8]) -> Option<T>
+where
+    T: Copy,
+{
+    // SAFETY: symbol lookup in a loaded library.
+    unsafe { library.get::<T>(symbol) }.ok().map(|value| *value)
+}
+
+fn resolve_bridge_dylib_path(
+    explicit_path: Option<&Path>,
+) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    if let Some(path) = explicit_path {
+        if path.exists() {
+            return Ok(path.to_path_buf());
+        }
+        return Err(io_error(format!(
+            "Fluid bridge dylib path does not exist: {}",
+            path.display()
+        )));
+    }
+
+    if let Ok(path) = std::env::var("GLIMPSE_FLUID_BRIDGE_DYLIB") {
+        let candidate = PathBuf::from(path);
+        if candidate.exists() {
+            return Ok(candidate);
+        }
+    }
+
+    let mut candidates = Vec::new();
+
+    if let Ok(executable) = std::env::current_exe() {
+        if let Some(parent) = executable.parent() {
+            candidates.push(parent.join("libGlimpseSpeechFluidBridge.dylib"));
+            candidates.push(parent.join("../Frameworks/libGlimpseSpeechFluidBridge.dylib"));
+            candidates.push(parent.join("../Resources/libGlimpseSpeechFluidBridge.dylib"));
+        }
+    }
+
+    #[cfg(debug_assertions)]
+    {
+        // Dev-only fallback for local workspace builds.
+        let bridge_build = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("swift-bridge/.build");
+        candidates.push(bridge_build.join("debug/libGlimpseSpeechFluidBridge.dylib"));
+        candidates.push(bridge_build.join("release/libGlimpseSpeechFluidBridge.dylib"));
+        candidates
+            .push(bridge_build.join("arm64-apple-macosx/debug/libGlimpseSpeechFluidBridge.dylib"));
+        candidates.push(
+            bridge_build.join("arm64-apple-macosx/release/libGlimpseSpeechFluidBridge.dylib"),
+        );
+        candidates
+            .push(bridge_build.join("x86_64-apple-macosx/debug/libGlimpseSpeechFluidBridge.dylib"));
+        candidates.push(
+            bridge_build.join("x86_64-apple-macosx/release/libGlimpseSpeechFluidBridge.dylib"),
+        );
+    }
+
+    if let Some(found) = candidates.into_iter().find(|candidate| candidate.exists()) {
+        return Ok(found);
+    }
+
+    Err(io_error(
+        "Fluid bridge dylib not found. Bundle libGlimpseSpeechFluidBridge.dylib near your app binary (or in ../Frameworks/../Resources), set GLIMPSE_FLUID_BRIDGE_DYLIB, or provide FluidModelParams::dylib_path",
+    ))
+}
+
+#[derive(Debug, Serialize)]
+struct BridgeConfigPayload {
+    schema_version: u32,
+    asr_model_dir: String,
+    diarization_model_dir: Option<String>,
+    runtime_macos_major: u32,
+    allow_network: bool,
+    model_version: Option<&'static str>,
+}
+
+#[derive(Debug, Serialize)]
+struct BridgeTranscribePayload {
+    schema_version: u32,
+    language_hint: Option<String>,
+    vocabulary: Vec<String>,
+    timestamps: &'static str,
+}
+
+#[derive(Debug, Serialize)]
+struct BridgeDiarizePayload {
+    schema_version: u32,
+    speaker_count: Option<u32>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BridgeEnvelope<T> {
+    schema_version: u32,
+    ok: bool,
+    data: Option<T>,
+    error: Option<FluidBridgeError>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BridgeTranscriptPayload {
+    text: String,
+    segments: Vec<BridgeSegmentPayload>,
+    #[serde(default)]
+    words: Option<Vec<BridgeSegmentPayload>>,
+    #[serde(default)]
+    tokens: Option<Vec<BridgeSegmentPayload>>,
+    #[serde(default)]
+    warnings: Vec<BridgeWarningPayload>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BridgeWarningPayload {
+    code: String,
+    message: String,
+}
+
+impl BridgeWarningPayload {
+    fn into_warning(self) -> Warning {
+        match self.code.as_str() {
+            "vocabulary_boosting_disabled" => Warning::VocabularyBoostingDisabled(self.message),
+            _ => Warning::Other(format!("{}: {}", self.code, self.message)),
+        }
+    }
+}
+
+/// A timed span of text: a segment, word or token.
+#[derive(Debug, Deserialize)]
+struct BridgeSegmentPayload {
+    start_ms: u64,
+    end_ms: u64,
+    text: String,
+}
+
+impl BridgeSegmentPayload {
+    fn is_empty(&self) -> bool {
+        self.end_ms <= self.start_ms || self.text.trim().is_empty()
+    }
+
+    fn start(&self) -> f32 {
+        self.start_ms as f32 / 1000.0
+    }
+
+    fn end(&self) -> f32 {
+        self.end_ms as f32 / 1000.0
+    }
+}
+
+impl BridgeTranscriptPayload {
+    fn into_transcription_result(self) -> TranscriptionResult {
+        let mut text = self.text.trim().to_string();
+        let mut warnings = self
+            .warnings
+            .into_iter()
+            .map(BridgeWarningPayload::into_warning)
+            .collect::<Vec<_>>();
+
+        // Blank segments carry nothing, so only timeless ones with text count as dropped.
+        let dropped = self
+            .segments
+            .iter()
+            .filter(|segment| segment.end_ms <= segment.start_ms && !segment.text.trim().is_empty())
+            .count();
+        if dropped > 0 {
+            warnings.push(Warning::SegmentsDropped(dropped));
+        }
+        let segments = self
+            .segments
+            .into_iter()
+            .filter(|segment| !segment.is_empty())
+            .map(|segment| TranscriptionSegment {
+                start: segment.start(),
+                end: segment.end(),
+                text: segment.text,
+                speaker: None,
+            })
+            .collect::<Vec<_>>();
+
+        if text.is_empty() {
+            text = segments
+                .iter()
+                .map(|segment| segment.text.trim())
+                .filter(|segment_text| !segment_text.is_empty())
+                .collect::<Vec<_>>()
+                .join("\n");
+        }
+
+        let segments = if segments.is_empty() {
+            None
+        } else {
+            Some(segments)
+        };
+
+        let words = self.words.map(|words| {
+            words
+                .into_iter()
+                .filter(|word| !word.is_empty())
+                .map(|word| TranscriptionWord {
+                    start: word.start(),
+                    end: word.end(),
+                    text: word.text.trim().to_string(),
+                })
+                .collect()
+        });
+        let tokens = self.tokens.map(|tokens| {
+            tokens
+                .into_iter()
+                .filter(|token| !token.is_empty())
+                .map(|token| TranscriptionToken {
+                    start: token.start(),
+                    end: token.end(),
+                    text: token.text,
+                })
+                .collect()
+        });
+
+        TranscriptionResult {
+            text,
+            segments,
+            words,
+            tokens,
+            warnings,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct BridgeDiarizationPayload {
+    turns: Vec<BridgeSpeakerTurnPayload>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BridgeSpeakerTurnPayload {
+    start_ms: u64,
+    end_ms: u64,
+    speaker: String,
+}
+
+impl BridgeDiarizationPayload {
+    fn into_speaker_turns(self) -> Vec<SpeakerTurn> {
+        self.turns
+            .into_iter()
+            .filter(|turn| turn.end_ms > turn.start_ms)
+            .map(|turn| SpeakerTurn {
+                start: turn.start_ms as f32 / 1000.0,
+                end: turn.end_ms as f32 / 1000.0,
+                speaker: turn.speaker,
+            })
+            .collect()
+    }
+}
+
+fn parse_bridge_payload<T>(bytes: &[u8], action: &str) -> Result<T, Box<dyn std::error::Error>>
+where
+    T: DeserializeOwned,
+{
+    let envelope: BridgeEnvelope<Value> = serde_json::from_slice(bytes).map_err(|error| {
+        io_error(format!(
+            "failed to decode Fluid {action} envelope: {error}; payload_preview={}",
+            preview_payload(bytes)
+        ))
+    })?;
+
+    if envelope.schema_version != BRIDGE_SCHEMA_VERSION {
+        return Err(io_error(format!(
+            "Fluid bridge schema mismatch: expected {}, got {}",
+            BRIDGE_SCHEMA_VERSION, envelope.schema_version
+        )));
+    }
+
+    if !envelope.ok {
+        let error = envelope
+            .error
+            .ok_or_else(|| io_error(format!("Fluid {action} failed without error payload")))?;
+
+        return Err(error.into());
+    }
+
+    let data = envelope
+        .data
+        .ok_or_else(|| io_error(format!("Fluid {action} succeeded without payload")))?;
+
+    serde_json::from_value(data)
+        .map_err(|error| io_error(format!("failed to decode Fluid {action} payload: {error}")))
+}
+
+fn preview_payload(bytes: &[u8]) -> String {
+    const MAX_PREVIEW_BYTES: usize = 240;
+    let len = bytes.len().min(MAX_PREVIEW_BYTES);
+    let snippet = String::from_utf8_lossy(&bytes[..len]);
+    if bytes.len() > MAX_PREVIEW_BYTES {
+        format!("{snippet}...")
+    } else {
+        snippet.into_owned()
+    }
+}
+
+fn normalize_language_hint(value: Option<&str>) -> Option<String> {
+    let raw = value?.trim();
+    if raw.is_empty() {
+        return None;
+    }
+
+    let base = raw.split(['-', '_']).next().unwrap_or(raw);
+    let normalized = base.trim().to_ascii_lowercase();
+    if normalized.is_empty() {
+        return None;
+    }
+
+    Some(normalized)
+}
+
+#[cfg(target_os = "macos")]
+fn detect_macos_major() -> Option<u32> {
+    use std::process::Command;
+
+    let output = Command::new("sw_vers")
+        .arg("-productVersion")
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    let version = String::from_utf8(output.stdout).ok()?;
+    version.trim().split('.').next()?.parse::<u32>().ok()
+}
+
+#[cfg(not(target_os = "macos"))]
+fn detect_macos_major() -> Option<u32> {
+    None
+}
+
+fn io_error(message: impl Into<String>) -> Box<dyn std::error::Error> {
+    std::io::Error::other(message.into()).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        forward_bridge_log, parse_bridge_payload, BridgeDiarizationPayload,
+        BridgeTranscriptPayload, FluidBridgeError, FluidTimestampGranularity, SpeakerTurn,
+        TranscriptionSegment, TranscriptionToken, TranscriptionWord, Warning,
+    };
+
+    #[test]
+    fn timestamp_granularity_wire_values_are_stable() {
+        assert_eq!(
+            FluidTimestampGranularity::WordPreferred.as_wire_value(),
+            "word_preferred"
+        );
+        assert_eq!(
+            FluidTimestampGranularity::SegmentsOnly.as_wire_value(),
+            "segments_only"
+        );
+        assert_eq!(FluidTimestampGranularity::Tokens.as_wire_value(), "tokens");
+    }
+
+    #[test]
+    fn parses_success_envelope() {
+        let json = br#"{"schema_version":1,"ok":true,"data":{"text":"hello","segments":[{"start_ms":0,"end_ms":500,"text":"hello"}]},"error":null}"#;
+        let payload: BridgeTranscriptPayload =
+            parse_bridge_payload(json, "transcribe").expect("valid envelope should parse");
+        let result = payload.into_transcription_result();
+
+        assert_eq!(result.text, "hello");
+        assert_eq!(
+            result.segments,
+            Some(vec![TranscriptionSegment {
+                start: 0.0,
+                end: 0.5,
+                text: "hello".to_string(),
+                speaker: None,
+            }])
+        );
+        assert!(result.warnings.is_empty());
+    }
+
+    #[test]
+    fn reports_bridge_warnings_and_dropped_segments() {
+        let json = br#"{"schema_version":1,"ok":true,"data":{"text":"hello there","segments":[{"start_ms":0,"end_ms":500,"text":"hello"},{"start_ms":500,"end_ms":500,"text":"there"},{"start_ms":600,"end_ms":600,"text":" "}],"warnings":[{"code":"vocabulary_boosting_disabled","message":"no CTC model"},{"code":"something_new","message":"details"}]},"error":null}"#;
+        let payload: BridgeTranscriptPayload =
+            parse_bridge_payload(json, "transcribe").expect("valid envelope should parse");
+        let result = payload.into_transcription_result();
+
+        assert_eq!(result.segments.map(|segments| segments.len()), Some(1));
+        assert_eq!(
+            result.warnings,
+            [
+                Warning::VocabularyBoostingDisabled("no CTC model".to_string()),
+                Warning::Other("something_new: details".to_string()),
+                Warning::SegmentsDropped(1),
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_words_and_tokens() {
+        let json = br#"{"schema_version":1,"ok":true,"data":{"text":"Garen here","segments":[{"start_ms":0,"end_ms":400,"text":"Garen here"}],"words":[{"start_ms":0,"end_ms":120,"text":"Garen","segment_index":0},{"start_ms":200,"end_ms":400,"text":"here","segment_index":0}],"tokens":[{"start_ms":0,"end_ms":50,"text":" G"},{"start_ms":50,"end_ms":120,"text":"aren"},{"start_ms":200,"end_ms":400,"text":" here"}]},"error":null}"#;
+        let payload: BridgeTranscriptPayload =
+            parse_bridge_payload(json, "transcribe").expect("valid envelope should parse");
+        let result = payload.into_transcription_result();
+
+        assert_eq!(
+            result.words.as_deref().map(|words| words
+                .iter()
+                .map(|word| word.text.as_str())
+                .collect::<Vec<_>>()),
+            Some(vec!["Garen", "here"])
+        );
+        assert_eq!(
+            result.words.unwrap()[1],
+            TranscriptionWord {
+                start: 0.2,
+                end: 0.4,
+                text: "here".to_string(),
+            }
+        );
+        let tokens = result.tokens.expect("tokens were sent");
+        assert_eq!(tokens.len(), 3);
+        assert_eq!(
+            tokens[1],
+            TranscriptionToken {
+                start: 0.05,
+                end: 0.12,
+                text: "aren".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn reports_bridge_error_payload() {
+        let json = br#"{"schema_version":1,"ok":false,"data":null,"error":{"code":"unsupported_platform","message":"macOS 13 is unsupported"}}"#;
+        let error = parse_bridge_payload::<BridgeTranscriptPayload>(json, "transcribe")
+            .expect_err("bridge error should map to error");
+
+        assert!(error.to_string().contains("unsupported_platform"));
+        let error = error
+            .downcast_ref::<FluidBridgeError>()
+            .expect("bridge errors keep their code");
+        assert_eq!(error.code, "unsupported_platform");
+        assert_eq!(error.message, "macOS 13 is unsupported");
+    }
+
+    #[test]
+    fn parses_diarization_turns() {
+        let json = br#"{"schema_version":1,"ok":true,"data":{"schema_version":1,"turns":[{"start_ms":0,"end_ms":1500,"speaker":"S1"},{"start_ms":1500,"end_ms":1500,"speaker":"S2"}]},"error":null}"#;
+        let payload: BridgeDiarizationPayload =
+            parse_bridge_payload(json, "diarize").expect("valid envelope should parse");
+
+        assert_eq!(
+            payload.into_speaker_turns(),
+            vec![SpeakerTurn {
+                start: 0.0,
+                end: 1.5,
+                speaker: "S1".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn forwards_bridge_logs_with_level_and_category() {
+        use std::sync::Mutex;
+
+        static RECORDS: Mutex<Vec<(log::Level, String, String)>> = Mutex::new(Vec::new());
+
+        struct Capture;
+
+        impl log::Log for Capture {
+            fn enabled(&self, _metadata: &log::Metadata<'_>) -> bool {
+                true
+            }
+
+            fn log(&self, record: &log::Record<'_>) {
+                RECORDS.lock().unwrap().push((
+                    record.level(),
+                    record.target().to_string(),
+                    record.args().to_string(),
+                ));
+            }
+
+            fn flush(&self) {}
+        }
+
+        log::set_logger(&Capture).expect("no other logger in unit tests");
+        log::set_max_level(log::LevelFilter::Trace);
+
+        let category = std::ffi::CString::new("vocabulary").unwrap();
+        let message = std::ffi::CString::new("boosting unavailable").unwrap();
+        forward_bridge_log(2, category.as_ptr(), message.as_ptr());
+        forward_bridge_log(4, std::ptr::null(), std::ptr::null());
+
+        let records = RECORDS.lock().unwrap();
+        let bridge_records = records
+            .iter()
+            .filter(|(_, target, _)| target.starts_with("glimpse_speech::fluid::bridge"))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            bridge_records,
+            [
+                &(
+                    log::Level::Warn,
+                    "glimpse_speech::fluid::bridge::vocabulary".to_string(),
+                    "boosting unavailable".to_string()
+                ),
+                &(
+                    log::Level::Debug,
+                    "glimpse_speech::fluid::bridge".to_string(),
+                    String::new()
+                ),
+            ]
+        );
+    }
+}
@@ -1,7 +1,12 @@
+pub mod any;
 #[cfg(feature = "fluid")]
 pub mod fluid;
 #[cfg(feature = "parakeet")]
 pub mod parakeet;
+#[cfg(feature = "onnx")]
+pub mod parakeet_onnx;
+#[cfg(feature = "remote")]
+pub mod remote;
 #[cfg(feature = "whisper")]
 pub mod whisper;
 #[cfg(feature = "whisperfile")]
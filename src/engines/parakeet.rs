@@ -1,32 +1,68 @@
 use std::path::{Path, PathBuf};
 
-use crate::{TranscriptionEngine, TranscriptionResult};
+use crate::{SpeakerTurn, TranscriptionEngine, TranscriptionResult};
 
+pub use super::fluid::AsrVersion;
 use super::fluid::{
     FluidEngine, FluidInferenceParams, FluidModelParams, FluidTimestampGranularity,
 };
 
 #[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum TimestampGranularity {
+    /// Sub-word tokens in `TranscriptionResult::tokens`, plus words and segments.
     #[default]
     Token,
+    /// Words in `TranscriptionResult::words`, plus segments.
     Word,
     Segment,
 }
 
 #[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum QuantizationType {
     #[default]
     FP32,
     Int8,
 }
 
+impl QuantizationType {
+    fn label(&self) -> &'static str {
+        match self {
+            Self::FP32 => "fp32",
+            Self::Int8 => "int8",
+        }
+    }
+
+    /// The variant a directory name stands for: `int8`, `fp32`, or a name ending in
+    /// `-int8`/`_int8` (and the same for `fp32`).
+    fn from_directory_name(name: &str) -> Option<Self> {
+        let name = name.to_ascii_lowercase();
+        [Self::Int8, Self::FP32].into_iter().find(|variant| {
+            let label = variant.label();
+            name == label
+                || name
+                    .strip_suffix(label)
+                    .is_some_and(|stem| stem.ends_with('-') || stem.ends_with('_'))
+        })
+    }
+}
+
 #[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ParakeetModelParams {
     pub quantization: QuantizationType,
     pub diarization_model_dir: Option<PathBuf>,
+    /// The Fluid bridge dylib, or the ONNX Runtime library for the `onnx` engine.
     pub dylib_path: Option<PathBuf>,
     pub runtime_macos_major: Option<u32>,
+    /// See [`FluidModelParams::allow_network`]; the `onnx` engine never uses the network.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub allow_network: bool,
+    /// See [`FluidModelParams::model_version`]. The `onnx` engine cannot tell the
+    /// versions apart, so there it only guards the language.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub model_version: Option<AsrVersion>,
 }
 
 impl ParakeetModelParams {
@@ -53,6 +89,7 @@ impl ParakeetModelParams {
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ParakeetInferenceParams {
     pub timestamp_granularity: TimestampGranularity,
     pub language: Option<String>,
@@ -85,6 +122,15 @@ impl ParakeetEngine {
             inner: FluidEngine::new(),
         }
     }
+
+    /// Speaker turns for a WAV file; see [`FluidEngine::diarize_file`].
+    pub fn diarize_file(
+        &mut self,
+        wav_path: &Path,
+        speaker_count: Option<u32>,
+    ) -> Result<Vec<SpeakerTurn>, Box<dyn std::error::Error>> {
+        self.inner.diarize_file(wav_path, speaker_count)
+    }
 }
 
 impl TranscriptionEngine for ParakeetEngine {
@@ -96,14 +142,18 @@ impl TranscriptionEngine for ParakeetEngine {
         model_path: &Path,
         params: Self::ModelParams,
     ) -> Result<(), Box<dyn std::error::Error>> {
-        let _ = params.quantization;
+        let model_dir = select_variant(model_path, &params.quantization)?;
+        #[cfg(feature = "tracing")]
+        tracing::debug!(variant = %model_dir.display(), "selected Parakeet model variant");
 
         self.inner.load_model_with_params(
-            model_path,
+            &model_dir,
             FluidModelParams {
                 diarization_model_dir: params.diarization_model_dir,
                 dylib_path: params.dylib_path,
                 runtime_macos_major: params.runtime_macos_major,
+                allow_network: params.allow_network,
+                model_version: params.model_version,
             },
         )
     }
@@ -131,14 +181,87 @@ impl TranscriptionEngine for ParakeetEngine {
     }
 }
 
+/// Finds the model files for `quantization` under `model_dir`, which is either a variant
+/// directory itself (`…/int8`, `…-fp32`), a directory holding `int8`/`fp32` variant
+/// directories, or a plain model without variants, which counts as FP32.
+fn select_variant(
+    model_dir: &Path,
+    quantization: &QuantizationType,
+) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    if !model_dir.is_dir() {
+        return Err(io_error(format!(
+            "Model directory not found: {}",
+            model_dir.display()
+        )));
+    }
+
+    let own_variant = model_dir
+        .file_name()
+        .and_then(|name| name.to_str())
+        .and_then(QuantizationType::from_directory_name);
+    let mut found = match own_variant {
+        Some(variant) => vec![(variant, model_dir.to_path_buf())],
+        None => variant_directories(model_dir)?,
+    };
+    if found.is_empty() {
+        found.push((QuantizationType::FP32, model_dir.to_path_buf()));
+    }
+
+    let mut matching = found
+        .iter()
+        .filter(|(variant, _)| variant == quantization)
+        .map(|(_, path)| path.clone())
+        .collect::<Vec<_>>();
+    match matching.len() {
+        1 => Ok(matching.remove(0)),
+        0 => Err(io_error(format!(
+            "no {} model variant in {}; found: {}",
+            quantization.label(),
+            model_dir.display(),
+            describe_variants(&found)
+        ))),
+        _ => Err(io_error(format!(
+            "several {} model variants in {}: {}",
+            quantization.label(),
+            model_dir.display(),
+            describe_variants(&found)
+        ))),
+    }
+}
+
+fn variant_directories(
+    model_dir: &Path,
+) -> Result<Vec<(QuantizationType, PathBuf)>, Box<dyn std::error::Error>> {
+    let mut variants = Vec::new();
+    for entry in std::fs::read_dir(model_dir)? {
+        let path = entry?.path();
+        let variant = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(QuantizationType::from_directory_name);
+        if let (Some(variant), true) = (variant, path.is_dir()) {
+            variants.push((variant, path));
+        }
+    }
+    variants.sort_by(|left, right| left.1.cmp(&right.1));
+    Ok(variants)
+}
+
+fn describe_variants(found: &[(QuantizationType, PathBuf)]) -> String {
+    found
+        .iter()
+        .map(|(variant, path)| format!("{} ({})", variant.label(), path.display()))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
 fn map_inference_params(params: Option<ParakeetInferenceParams>) -> FluidInferenceParams {
     let params = params.unwrap_or_default();
 
     let timestamp_granularity = match params.timestamp_granularity {
         TimestampGranularity::Segment => FluidTimestampGranularity::SegmentsOnly,
-        TimestampGranularity::Token | TimestampGranularity::Word => {
-            FluidTimestampGranularity::WordPreferred
-        }
+        TimestampGranularity::Word => FluidTimestampGranularity::WordPreferred,
+        TimestampGranularity::Token => FluidTimestampGranularity::Tokens,
     };
 
     FluidInferenceParams {
@@ -148,19 +271,102 @@ fn map_inference_params(params: Option<ParakeetInferenceParams>) -> FluidInferen
     }
 }
 
+fn io_error(message: impl Into<String>) -> Box<dyn std::error::Error> {
+    std::io::Error::other(message.into()).into()
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{ParakeetModelParams, QuantizationType};
+    use std::fs;
+    use std::path::{Path, PathBuf};
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    use super::{
+        map_inference_params, select_variant, ParakeetInferenceParams, QuantizationType,
+        TimestampGranularity,
+    };
+    use crate::engines::fluid::FluidTimestampGranularity;
 
     #[test]
-    fn int8_constructor_sets_quantized_mode() {
-        let params = ParakeetModelParams::int8();
-        assert_eq!(params.quantization, QuantizationType::Int8);
+    fn each_granularity_maps_to_its_own_wire_value() {
+        let wire = |timestamp_granularity| {
+            map_inference_params(Some(ParakeetInferenceParams {
+                timestamp_granularity,
+                ..Default::default()
+            }))
+            .timestamp_granularity
+        };
+
+        assert_eq!(
+            wire(TimestampGranularity::Token),
+            FluidTimestampGranularity::Tokens
+        );
+        assert_eq!(
+            wire(TimestampGranularity::Word),
+            FluidTimestampGranularity::WordPreferred
+        );
+        assert_eq!(
+            wire(TimestampGranularity::Segment),
+            FluidTimestampGranularity::SegmentsOnly
+        );
+    }
+
+    fn model_tree(name: &str, directories: &[&str]) -> PathBuf {
+        let nonce = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let root = std::env::temp_dir().join(format!("glimpse-parakeet-{name}-{nonce}"));
+        for directory in directories {
+            fs::create_dir_all(root.join(directory)).unwrap();
+        }
+        fs::create_dir_all(&root).unwrap();
+        root
+    }
+
+    fn select(root: &Path, quantization: QuantizationType) -> Result<PathBuf, String> {
+        select_variant(root, &quantization).map_err(|error| error.to_string())
     }
 
     #[test]
-    fn fp32_constructor_sets_full_precision_mode() {
-        let params = ParakeetModelParams::fp32();
-        assert_eq!(params.quantization, QuantizationType::FP32);
+    fn selects_the_requested_variant_directory() {
+        let root = model_tree("both", &["int8", "parakeet-tdt-0.6b-v3-fp32", "notes"]);
+
+        assert_eq!(select(&root, QuantizationType::Int8), Ok(root.join("int8")));
+        assert_eq!(
+            select(&root, QuantizationType::FP32),
+            Ok(root.join("parakeet-tdt-0.6b-v3-fp32"))
+        );
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn missing_variant_lists_what_was_found() {
+        let root = model_tree("fp32-only", &["fp32"]);
+
+        let error = select(&root, QuantizationType::Int8).unwrap_err();
+        assert!(error.contains("no int8 model variant"), "{error}");
+        assert!(error.contains(&format!("fp32 ({})", root.join("fp32").display())));
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn plain_model_directory_is_fp32() {
+        let root = model_tree("plain", &["parakeet-tdt-0.6b-v3-coreml"]);
+
+        assert_eq!(select(&root, QuantizationType::FP32), Ok(root.clone()));
+        assert!(select(&root, QuantizationType::Int8).is_err());
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn accepts_a_variant_directory_directly() {
+        let root = model_tree("direct", &["int8"]);
+
+        let int8 = root.join("int8");
+        assert_eq!(select(&int8, QuantizationType::Int8), Ok(int8.clone()));
+        assert!(select(&int8, QuantizationType::FP32).is_err());
+        assert!(select(&root.join("missing"), QuantizationType::FP32).is_err());
+        fs::remove_dir_all(root).unwrap();
     }
 }
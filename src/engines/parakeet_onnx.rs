@@ -0,0 +1,702 @@
+//! Parakeet TDT/CTC on ONNX Runtime, for platforms without the Fluid bridge.
+//!
+//! Expects a directory with the exported NeMo models, e.g.
+//! `istupakov/parakeet-tdt-0.6b-v3-onnx`: `encoder-model.onnx` and
+//! `decoder_joint-model.onnx` (TDT) or `model.onnx` (CTC), `vocab.txt`, and optionally
+//! `config.json`. Int8 models use the `.int8.onnx` files next to them.
+
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+
+use ort::session::Session;
+use ort::value::Tensor;
+
+use crate::audio::features::{LogMelConfig, LogMelExtractor};
+use crate::vocabulary::Vocabulary;
+use crate::{
+    TranscriptionEngine, TranscriptionResult, TranscriptionSegment, TranscriptionToken,
+    TranscriptionWord, Warning,
+};
+
+use super::parakeet::{
+    AsrVersion, ParakeetInferenceParams, ParakeetModelParams, QuantizationType,
+    TimestampGranularity,
+};
+
+/// Tokens the TDT decoder may emit on one encoder frame before it is forced to move on.
+const MAX_SYMBOLS_PER_STEP: usize = 10;
+
+/// Runs Parakeet on the CPU through ONNX Runtime. A drop-in replacement for
+/// [`super::parakeet::ParakeetEngine`] that works on Linux and Windows.
+///
+/// ONNX Runtime is loaded at runtime from `ParakeetModelParams::dylib_path`, the
+/// `ORT_DYLIB_PATH` environment variable, or the system library path. The `language` and
+/// diarization options are ignored; `vocabulary` terms fix near-misses in the text.
+#[derive(Default)]
+pub struct ParakeetOnnxEngine {
+    model: Option<OnnxModel>,
+}
+
+struct OnnxModel {
+    kind: ModelKind,
+    vocabulary: Vec<String>,
+    blank: usize,
+    features: LogMelExtractor,
+    frame_seconds: f32,
+    version: Option<AsrVersion>,
+}
+
+enum ModelKind {
+    Tdt {
+        encoder: Session,
+        decoder_joint: Session,
+        state_shapes: [Vec<usize>; 2],
+    },
+    Ctc {
+        model: Session,
+    },
+}
+
+impl ParakeetOnnxEngine {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn decode(&mut self, samples: &[f32]) -> Result<Vec<DecodedToken>, Box<dyn std::error::Error>> {
+        let model = self
+            .model
+            .as_mut()
+            .ok_or_else(|| io_error("Model not loaded"))?;
+        let features = model.features.compute(samples);
+        let frames = features.frames();
+        let audio_signal =
+            Tensor::from_array(([1, features.n_mels(), frames], features.into_data()))?;
+        let length = Tensor::from_array(([1], vec![frames as i64]))?;
+
+        match &mut model.kind {
+            ModelKind::Ctc { model: session } => {
+                let outputs = session.run(ort::inputs![
+                    "audio_signal" => audio_signal,
+                    "length" => length,
+                ])?;
+                let (shape, logprobs) = outputs["logprobs"].try_extract_tensor::<f32>()?;
+                let vocab_size = *shape.last().unwrap_or(&0) as usize;
+                Ok(ctc_greedy(logprobs, vocab_size, model.blank))
+            }
+            ModelKind::Tdt {
+                encoder,
+                decoder_joint,
+                state_shapes,
+            } => {
+                let (encoded, dim) = {
+                    let outputs = encoder.run(ort::inputs![
+                        "audio_signal" => audio_signal,
+                        "length" => length,
+                    ])?;
+                    let (shape, values) = outputs["outputs"].try_extract_tensor::<f32>()?;
+                    let (_, lengths) = outputs["encoded_lengths"].try_extract_tensor::<i64>()?;
+                    let dim = shape.get(1).copied().unwrap_or(0) as usize;
+                    let time = shape.get(2).copied().unwrap_or(0) as usize;
+                    let valid = lengths.first().map_or(time, |&length| length as usize);
+                    (frame_major(values, dim, time, valid.min(time)), dim)
+                };
+                let frames = encoded.len() / dim.max(1);
+                let vocab_size = model.vocabulary.len();
+                let initial = state_shapes
+                    .clone()
+                    .map(|shape| (vec![0.0; shape.iter().product()], shape));
+
+                tdt_greedy(
+                    frames,
+                    vocab_size,
+                    model.blank,
+                    initial,
+                    |frame, previous, state| {
+                        let frame = &encoded[frame * dim..(frame + 1) * dim];
+                        let [state_1, state_2] = state;
+                        let outputs = decoder_joint.run(ort::inputs![
+                            "encoder_outputs" => Tensor::from_array(([1, dim, 1], frame.to_vec()))?,
+                            "targets" => Tensor::from_array(([1, 1], vec![previous as i32]))?,
+                            "target_length" => Tensor::from_array(([1], vec![1i32]))?,
+                            "input_states_1" => Tensor::from_array((state_1.1.clone(), state_1.0.clone()))?,
+                            "input_states_2" => Tensor::from_array((state_2.1.clone(), state_2.0.clone()))?,
+                        ])?;
+                        let (_, logits) = outputs["outputs"].try_extract_tensor::<f32>()?;
+                        let next = ["output_states_1", "output_states_2"].map(|name| {
+                            outputs[name]
+                                .try_extract_tensor::<f32>()
+                                .map(|(shape, values)| {
+                                    let shape = shape.iter().map(|&dim| dim as usize).collect();
+                                    (values.to_vec(), shape)
+                                })
+                        });
+                        let [next_1, next_2] = next;
+                        Ok((logits.to_vec(), [next_1?, next_2?]))
+                    },
+                )
+            }
+        }
+    }
+}
+
+impl TranscriptionEngine for ParakeetOnnxEngine {
+    type InferenceParams = ParakeetInferenceParams;
+    type ModelParams = ParakeetModelParams;
+
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            level = "info",
+            skip_all,
+            fields(engine = "parakeet-onnx", model = %model_path.display()),
+            err
+        )
+    )]
+    fn load_model_with_params(
+        &mut self,
+        model_path: &Path,
+        params: Self::ModelParams,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let files = ModelFiles::find(model_path, &params.quantization)?;
+        let vocabulary = parse_vocabulary(&std::fs::read_to_string(&files.vocabulary)?)?;
+        let blank = vocabulary
+            .iter()
+            .position(|token| token == "<blk>")
+            .unwrap_or(vocabulary.len() - 1);
+        let config = match &files.config {
+            Some(path) => serde_json::from_str(&std::fs::read_to_string(path)?)?,
+            None => {
+                #[cfg(feature = "tracing")]
+                tracing::debug!("no config.json; using default feature settings");
+                serde_json::Value::Null
+            }
+        };
+        let setting = |key: &str, default: usize| {
+            config
+                .get(key)
+                .and_then(serde_json::Value::as_u64)
+                .map_or(default, |value| value as usize)
+        };
+        let features = LogMelExtractor::new(LogMelConfig::nemo(setting("features_size", 128)))?;
+        let subsampling = setting("subsampling_factor", 8);
+        let frame_seconds = (features.config().hop_length * subsampling) as f32
+            / features.config().sample_rate as f32;
+
+        // ort panics when it cannot load the ONNX Runtime library.
+        let kind = catch_unwind(AssertUnwindSafe(|| {
+            if let Some(dylib_path) = &params.dylib_path {
+                ort::init_from(dylib_path.display().to_string()).commit()?;
+            }
+            files.load_sessions()
+        }))
+        .map_err(|_| {
+            io_error(
+                "failed to load ONNX Runtime; install it, set ORT_DYLIB_PATH, or provide ParakeetModelParams::dylib_path",
+            )
+        })??;
+
+        self.model = Some(OnnxModel {
+            kind,
+            vocabulary,
+            blank,
+            features,
+            frame_seconds,
+            version: params.model_version,
+        });
+        Ok(())
+    }
+
+    fn unload_model(&mut self) {
+        self.model = None;
+    }
+
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            level = "debug",
+            skip_all,
+            fields(engine = "parakeet-onnx", samples = samples.len()),
+            err
+        )
+    )]
+    fn transcribe_samples(
+        &mut self,
+        samples: Vec<f32>,
+        params: Option<Self::InferenceParams>,
+    ) -> Result<TranscriptionResult, Box<dyn std::error::Error>> {
+        let params = params.unwrap_or_default();
+        if let Some(version) = self.model.as_ref().and_then(|model| model.version) {
+            version.check_language(params.language.as_deref())?;
+        }
+        let decoded = self.decode(&samples)?;
+        let model = self
+            .model
+            .as_ref()
+            .ok_or_else(|| io_error("Model not loaded"))?;
+
+        let mut result = assemble_result(
+            &decoded,
+            &model.vocabulary,
+            model.frame_seconds,
+            &params.timestamp_granularity,
+        );
+        if let Some(language) = params.language.filter(|language| !language.is_empty()) {
+            result.warnings.push(Warning::LanguageHintIgnored(language));
+        }
+        let vocabulary = Vocabulary::new(&params.vocabulary);
+        if !vocabulary.is_empty() {
+            result.text = vocabulary.correct(&result.text);
+            for segment in result.segments.iter_mut().flatten() {
+                segment.text = vocabulary.correct(&segment.text);
+            }
+        }
+        Ok(result)
+    }
+}
+
+/// The ONNX files for one quantization in a model directory.
+#[derive(Debug, PartialEq)]
+struct ModelFiles {
+    /// `encoder-model` for TDT, `model` for CTC.
+    encoder: PathBuf,
+    /// `decoder_joint-model`; `None` for CTC.
+    decoder_joint: Option<PathBuf>,
+    vocabulary: PathBuf,
+    config: Option<PathBuf>,
+}
+
+impl ModelFiles {
+    fn find(
+        model_dir: &Path,
+        quantization: &QuantizationType,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        if !model_dir.is_dir() {
+            return Err(io_error(format!(
+                "Model directory not found: {}",
+                model_dir.display()
+            )));
+        }
+        let suffix = match quantization {
+            QuantizationType::FP32 => ".onnx",
+            QuantizationType::Int8 => ".int8.onnx",
+        };
+        let file = |stem: &str| {
+            let path = model_dir.join(format!("{stem}{suffix}"));
+            path.is_file().then_some(path)
+        };
+        let vocabulary = model_dir.join("vocab.txt");
+        if !vocabulary.is_file() {
+            return Err(io_error(format!(
+                "vocab.txt not found in {}",
+                model_dir.display()
+            )));
+        }
+        let config = Some(model_dir.join("config.json")).filter(|path| path.is_file());
+
+        match (
+            file("encoder-model"),
+            file("decoder_joint-model"),
+            file("model"),
+        ) {
+            (Some(encoder), Some(decoder_joint), _) => Ok(Self {
+                encoder,
+                decoder_joint: Some(decoder_joint),
+                vocabulary,
+                config,
+            }),
+            (_, _, Some(encoder)) => Ok(Self {
+                encoder,
+                decoder_joint: None,
+                vocabulary,
+                config,
+            }),
+            _ => {
+                let mut found = std::fs::read_dir(model_dir)?
+                    .filter_map(|entry| entry.ok())
+                    .map(|entry| entry.file_name().to_string_lossy().into_owned())
+                    .filter(|name| name.ends_with(".onnx"))
+                    .collect::<Vec<_>>();
+                found.sort();
+                Err(io_error(format!(
+                    "no Parakeet ONNX model in {}: expected encoder-model{suffix} and decoder_joint-model{suffix} (TDT) or model{suffix} (CTC); found: {}",
+                    model_dir.display(),
+                    if found.is_empty() {
+                        "no .onnx files".to_string()
+                    } else {
+                        found.join(", ")
+                    }
+                )))
+            }
+        }
+    }
+
+    fn load_sessions(&self) -> Result<ModelKind, Box<dyn std::error::Error>> {
+        let encoder = Session::builder()?.commit_from_file(&self.encoder)?;
+        let Some(decoder_joint) = &self.decoder_joint else {
+            return Ok(ModelKind::Ctc { model: encoder });
+        };
+        let decoder_joint = Session::builder()?.commit_from_file(decoder_joint)?;
+
+        // Decoder states are [layers, batch, hidden]; the batch dimension is dynamic.
+        let state_shape = |name: &str| {
+            decoder_joint
+                .inputs
+                .iter()
+                .find(|input| input.name == name)
+                .and_then(|input| input.input_type.tensor_shape())
+                .map(|shape| shape.iter().map(|&dim| dim.max(1) as usize).collect())
+                .ok_or_else(|| io_error(format!("decoder_joint model has no {name} input")))
+        };
+        let state_shapes = [
+            state_shape("input_states_1")?,
+            state_shape("input_states_2")?,
+        ];
+        Ok(ModelKind::Tdt {
+            encoder,
+            decoder_joint,
+            state_shapes,
+        })
+    }
+}
+
+/// `vocab.txt` lines are `<piece> <id>`.
+fn parse_vocabulary(contents: &str) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+    let mut entries = Vec::new();
+    for line in contents.lines().filter(|line| !line.trim().is_empty()) {
+        let (piece, id) = line
+            .rsplit_once(' ')
+            .ok_or_else(|| io_error(format!("invalid vocab.txt line: {line}")))?;
+        let id: usize = id
+            .trim()
+            .parse()
+            .map_err(|_| io_error(format!("invalid vocab.txt line: {line}")))?;
+        entries.push((id, piece.to_string()));
+    }
+    if entries.is_empty() {
+        return Err(io_error("vocab.txt is empty"));
+    }
+    entries.sort_by_key(|(id, _)| *id);
+    if entries
+        .iter()
+        .enumerate()
+        .any(|(index, (id, _))| index != *id)
+    {
+        return Err(io_error("vocab.txt ids are not contiguous"));
+    }
+    Ok(entries.into_iter().map(|(_, piece)| piece).collect())
+}
+
+/// Encoder output `[dim, time]` as `time` consecutive vectors of `dim` values.
+fn frame_major(values: &[f32], dim: usize, time: usize, valid: usize) -> Vec<f32> {
+    let mut frames = Vec::with_capacity(dim * valid);
+    for frame in 0..valid {
+        frames.extend((0..dim).map(|channel| values[channel * time + frame]));
+    }
+    frames
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct DecodedToken {
+    id: usize,
+    /// Encoder frame the token was emitted on.
+    frame: usize,
+    /// Frames the token covers.
+    duration: usize,
+}
+
+/// Greedy TDT decoding. `joint(frame, previous_token, state)` runs the prediction and joint
+/// networks and returns the token logits (blank included) followed by the duration logits,
+/// plus the prediction network state to keep if a token is emitted.
+fn tdt_greedy<S>(
+    frames: usize,
+    vocab_size: usize,
+    blank: usize,
+    initial_state: S,
+    mut joint: impl FnMut(usize, usize, &S) -> Result<(Vec<f32>, S), Box<dyn std::error::Error>>,
+) -> Result<Vec<DecodedToken>, Box<dyn std::error::Error>> {
+    let mut tokens: Vec<DecodedToken> = Vec::new();
+    let mut state = initial_state;
+    let mut frame = 0;
+    let mut emitted = 0;
+
+    while frame < frames {
+        let previous = tokens.last().map_or(blank, |token| token.id);
+        let (logits, next_state) = joint(frame, previous, &state)?;
+        if logits.len() <= vocab_size {
+            return Err(io_error("decoder_joint output has no duration logits"));
+        }
+        let token = argmax(&logits[..vocab_size]);
+        let duration = argmax(&logits[vocab_size..]);
+
+        if token != blank {
+            state = next_state;
+            tokens.push(DecodedToken {
+                id: token,
+                frame,
+                duration: duration.max(1),
+            });
+            emitted += 1;
+        }
+
+        if duration > 0 {
+            frame += duration;
+            emitted = 0;
+        } else if token == blank || emitted == MAX_SYMBOLS_PER_STEP {
+            frame += 1;
+            emitted = 0;
+        }
+    }
+    Ok(tokens)
+}
+
+/// Greedy CTC decoding over `[frames, vocab_size]` log-probabilities.
+fn ctc_greedy(logprobs: &[f32], vocab_size: usize, blank: usize) -> Vec<DecodedToken> {
+    let mut tokens: Vec<DecodedToken> = Vec::new();
+    let mut previous = blank;
+    for (frame, row) in logprobs.chunks(vocab_size.max(1)).enumerate() {
+        let token = argmax(row);
+        if token == previous && token != blank {
+            if let Some(last) = tokens.last_mut() {
+                last.duration += 1;
+            }
+        } else if token != blank {
+            tokens.push(DecodedToken {
+                id: token,
+                frame,
+                duration: 1,
+            });
+        }
+        previous = token;
+    }
+    tokens
+}
+
+fn argmax(values: &[f32]) -> usize {
+    values
+        .iter()
+        .enumerate()
+        .fold((0, f32::NEG_INFINITY), |best, (index, &value)| {
+            if value > best.1 {
+                (index, value)
+            } else {
+                best
+            }
+        })
+        .0
+}
+
+/// Turns decoded tokens into text, tokens, words and sentence segments.
+fn assemble_result(
+    decoded: &[DecodedToken],
+    vocabulary: &[String],
+    frame_seconds: f32,
+    granularity: &TimestampGranularity,
+) -> TranscriptionResult {
+    let tokens = decoded
+        .iter()
+        .filter_map(|token| {
+            let piece = vocabulary.get(token.id)?;
+            // Control tokens such as `<unk>` or `<|en|>` are not speech.
+            if piece.starts_with('<') && piece.ends_with('>') {
+                return None;
+            }
+            Some(TranscriptionToken {
+                start: token.frame as f32 * frame_seconds,
+                end: (token.frame + token.duration) as f32 * frame_seconds,
+                text: piece.replace('\u{2581}', " "),
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let mut words: Vec<TranscriptionWord> = Vec::new();
+    for token in &tokens {
+        match words.last_mut() {
+            Some(word) if !token.text.starts_with(' ') => {
+                word.text.push_str(&token.text);
+                word.end = token.end;
+            }
+            _ => words.push(TranscriptionWord {
+                start: token.start,
+                end: token.end,
+                text: token.text.trim().to_string(),
+            }),
+        }
+    }
+    words.retain(|word| !word.text.is_empty());
+
+    let mut segments: Vec<TranscriptionSegment> = Vec::new();
+    let mut sentence_open = false;
+    for word in &words {
+        match segments.last_mut() {
+            Some(segment) if sentence_open => {
+                segment.text.push(' ');
+                segment.text.push_str(&word.text);
+                segment.end = word.end;
+            }
+            _ => segments.push(TranscriptionSegment {
+                start: word.start,
+                end: word.end,
+                text: word.text.clone(),
+                speaker: None,
+            }),
+        }
+        sentence_open = !word.text.ends_with(['.', '?', '!']);
+    }
+
+    let text = tokens
+        .iter()
+        .map(|token| token.text.as_str())
+        .collect::<String>()
+        .trim()
+        .to_string();
+    TranscriptionResult {
+        text,
+        segments: (!segments.is_empty()).then_some(segments),
+        words: (*granularity != TimestampGranularity::Segment).then_some(words),
+        tokens: (*granularity == TimestampGranularity::Token).then_some(tokens),
+        warnings: Vec::new(),
+    }
+}
+
+fn io_error(message: impl Into<String>) -> Box<dyn std::error::Error> {
+    std::io::Error::other(message.into()).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    use super::{
+        assemble_result, ctc_greedy, parse_vocabulary, tdt_greedy, DecodedToken, ModelFiles,
+    };
+    use crate::engines::parakeet::{QuantizationType, TimestampGranularity};
+
+    fn token(id: usize, frame: usize, duration: usize) -> DecodedToken {
+        DecodedToken {
+            id,
+            frame,
+            duration,
+        }
+    }
+
+    #[test]
+    fn tdt_greedy_follows_predicted_durations() {
+        // Vocabulary of 3 with blank = 2, then durations 0..=2.
+        let script = [
+            (0, [5.0, 0.0, 0.0, 5.0, 0.0, 0.0]), // token 0, stay on frame 0
+            (0, [0.0, 5.0, 0.0, 0.0, 0.0, 5.0]), // token 1, skip two frames
+            (2, [0.0, 0.0, 5.0, 0.0, 5.0, 0.0]), // blank, advance one
+        ];
+        let mut step = 0;
+        let tokens = tdt_greedy(3, 3, 2, 0usize, |frame, previous, state| {
+            let (expected_frame, logits) = script[step];
+            assert_eq!(frame, expected_frame);
+            assert_eq!(*state, step.min(2));
+            assert_eq!(previous, [2, 0, 1][step]);
+            step += 1;
+            Ok((logits.to_vec(), state + 1))
+        })
+        .unwrap();
+
+        assert_eq!(tokens, [token(0, 0, 1), token(1, 0, 2)]);
+    }
+
+    #[test]
+    fn tdt_greedy_caps_symbols_per_frame() {
+        let tokens = tdt_greedy(1, 2, 1, (), |_, _, _| Ok((vec![1.0, 0.0, 1.0], ()))).unwrap();
+
+        assert_eq!(tokens.len(), super::MAX_SYMBOLS_PER_STEP);
+    }
+
+    #[test]
+    fn ctc_greedy_collapses_repeats_and_blanks() {
+        let frames = [
+            [0.0, 1.0, 0.0],
+            [0.0, 1.0, 0.0],
+            [0.0, 0.0, 1.0],
+            [0.0, 1.0, 0.0],
+        ];
+        let logprobs = frames.concat();
+
+        assert_eq!(
+            ctc_greedy(&logprobs, 3, 2),
+            [token(1, 0, 2), token(1, 3, 1)]
+        );
+    }
+
+    #[test]
+    fn assembles_tokens_words_and_sentences() {
+        let vocabulary = ["<unk>", "\u{2581}G", "aren", "\u{2581}here.", "\u{2581}Hi"]
+            .map(String::from)
+            .to_vec();
+        let decoded = [
+            token(0, 0, 1),
+            token(1, 1, 1),
+            token(2, 2, 2),
+            token(3, 5, 1),
+            token(4, 8, 1),
+        ];
+
+        let result = assemble_result(&decoded, &vocabulary, 0.08, &TimestampGranularity::Token);
+
+        assert_eq!(result.text, "Garen here. Hi");
+        let tokens = result.tokens.unwrap();
+        assert_eq!(tokens.len(), 4);
+        assert_eq!(tokens[1].text, "aren");
+        let words = result.words.unwrap();
+        assert_eq!(words[0].text, "Garen");
+        assert!((words[0].end - 0.32).abs() < 1e-6);
+        let segments = result.segments.unwrap();
+        assert_eq!(segments.len(), 2);
+        assert_eq!(segments[0].text, "Garen here.");
+
+        let words_only = assemble_result(&decoded, &vocabulary, 0.08, &TimestampGranularity::Word);
+        assert!(words_only.tokens.is_none() && words_only.words.is_some());
+        let segments_only =
+            assemble_result(&decoded, &vocabulary, 0.08, &TimestampGranularity::Segment);
+        assert!(segments_only.words.is_none());
+    }
+
+    #[test]
+    fn parses_vocabulary_by_id() {
+        let vocabulary = parse_vocabulary("b 1\na 0\n<blk> 2\n").unwrap();
+
+        assert_eq!(vocabulary, ["a", "b", "<blk>"]);
+        assert!(parse_vocabulary("a 0\nb 2\n").is_err());
+    }
+
+    #[test]
+    fn finds_tdt_ctc_and_reports_missing_variants() {
+        let nonce = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let root = std::env::temp_dir().join(format!("glimpse-onnx-files-{nonce}"));
+        fs::create_dir_all(&root).unwrap();
+        for name in [
+            "encoder-model.int8.onnx",
+            "decoder_joint-model.int8.onnx",
+            "vocab.txt",
+        ] {
+            fs::write(root.join(name), b"").unwrap();
+        }
+
+        let int8 = ModelFiles::find(&root, &QuantizationType::Int8).unwrap();
+        assert_eq!(int8.encoder, root.join("encoder-model.int8.onnx"));
+        assert!(int8.decoder_joint.is_some());
+
+        let error = ModelFiles::find(&root, &QuantizationType::FP32)
+            .unwrap_err()
+            .to_string();
+        assert!(
+            error.contains("found: decoder_joint-model.int8.onnx, encoder-model.int8.onnx"),
+            "{error}"
+        );
+
+        fs::write(root.join("model.onnx"), b"").unwrap();
+        let ctc = ModelFiles::find(&root, &QuantizationType::FP32).unwrap();
+        assert_eq!(ctc.decoder_joint, None);
+        fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -0,0 +1,343 @@
+use std::path::Path;
+use std::time::Duration;
+
+use crate::http::{MultipartForm, VerboseJsonPayload};
+use crate::{TranscriptionEngine, TranscriptionResult};
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RemoteModelParams {
+    /// Sent as `Authorization: Bearer <api_key>`.
+    pub api_key: Option<String>,
+    /// Extra headers added to every request, e.g. a gateway token.
+    pub headers: Vec<(String, String)>,
+    pub timeout_secs: u64,
+    /// Retries after a 5xx response or a connection failure, timeout or DNS error;
+    /// `0` disables retrying.
+    pub max_retries: u32,
+    /// Delay before the first retry, doubled on each following attempt.
+    pub retry_backoff_ms: u64,
+}
+
+impl Default for RemoteModelParams {
+    fn default() -> Self {
+        Self {
+            api_key: None,
+            headers: Vec::new(),
+            timeout_secs: 120,
+            max_retries: 2,
+            retry_backoff_ms: 500,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum TimestampGranularity {
+    Word,
+    Segment,
+}
+
+impl TimestampGranularity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TimestampGranularity::Word => "word",
+            TimestampGranularity::Segment => "segment",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RemoteInferenceParams {
+    pub language: Option<String>,
+    pub prompt: Option<String>,
+    /// Sampling temperature in `0.0..=1.0`; the server default when `None`.
+    pub temperature: Option<f32>,
+    pub timestamp_granularities: Vec<TimestampGranularity>,
+}
+
+impl Default for RemoteInferenceParams {
+    fn default() -> Self {
+        Self {
+            language: None,
+            prompt: None,
+            temperature: None,
+            timestamp_granularities: vec![TimestampGranularity::Segment],
+        }
+    }
+}
+
+/// Transcribes through an OpenAI-compatible `/audio/transcriptions` endpoint.
+///
+/// The "model path" passed to `load_model` is the model name sent to the server
+/// (e.g. `whisper-1` or `Systran/faster-whisper-large-v3`); nothing is read from disk.
+pub struct RemoteEngine {
+    base_url: String,
+    client: Option<RemoteClient>,
+}
+
+impl RemoteEngine {
+    /// `base_url` is the API root including the version, e.g. `http://gpu-box:8000/v1`.
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self {
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            client: None,
+        }
+    }
+
+    pub fn endpoint(&self) -> String {
+        format!("{}/audio/transcriptions", self.base_url)
+    }
+}
+
+impl Default for RemoteEngine {
+    fn default() -> Self {
+        Self::new("https://api.openai.com/v1")
+    }
+}
+
+impl TranscriptionEngine for RemoteEngine {
+    type InferenceParams = RemoteInferenceParams;
+    type ModelParams = RemoteModelParams;
+
+    fn load_model_with_params(
+        &mut self,
+        model_path: &Path,
+        params: Self::ModelParams,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let model = model_path.to_string_lossy().trim().to_string();
+        if model.is_empty() {
+            return Err(io_error("Remote model name must not be empty"));
+        }
+
+        let agent = ureq::Agent::new_with_config(
+            ureq::Agent::config_builder()
+                .http_status_as_error(false)
+                .timeout_global(Some(Duration::from_secs(params.timeout_secs)))
+                .build(),
+        );
+
+        self.client = Some(RemoteClient {
+            endpoint: self.endpoint(),
+            model,
+            params,
+            agent,
+        });
+        Ok(())
+    }
+
+    fn unload_model(&mut self) {
+        self.client = None;
+    }
+
+    fn transcribe_samples(
+        &mut self,
+        samples: Vec<f32>,
+        params: Option<Self::InferenceParams>,
+    ) -> Result<TranscriptionResult, Box<dyn std::error::Error>> {
+        let client = self
+            .client
+            .as_ref()
+            .ok_or_else(|| io_error("Model not loaded. Call load_model() first."))?;
+
+        let wav = crate::audio::encode_wav_samples(&samples)?;
+        client.transcribe("audio.wav", &wav, &params.unwrap_or_default())
+    }
+
+    fn transcribe_file(
+        &mut self,
+        wav_path: &Path,
+        params: Option<Self::InferenceParams>,
+    ) -> Result<TranscriptionResult, Box<dyn std::error::Error>> {
+        let client = self
+            .client
+            .as_ref()
+            .ok_or_else(|| io_error("Model not loaded. Call load_model() first."))?;
+
+        let audio = std::fs::read(wav_path).map_err(|error| {
+            io_error(format!(
+                "failed to read audio file {}: {error}",
+                wav_path.display()
+            ))
+        })?;
+        let file_name = wav_path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "audio.wav".to_string());
+
+        client.transcribe(&file_name, &audio, &params.unwrap_or_default())
+    }
+}
+
+struct RemoteClient {
+    endpoint: String,
+    model: String,
+    params: RemoteModelParams,
+    agent: ureq::Agent,
+}
+
+impl RemoteClient {
+    fn transcribe(
+        &self,
+        file_name: &str,
+        audio: &[u8],
+        params: &RemoteInferenceParams,
+    ) -> Result<TranscriptionResult, Box<dyn std::error::Error>> {
+        let mut attempt = 0;
+        loop {
+            // The form is rebuilt per attempt so every retry gets a fresh boundary.
+            let form = transcription_form(&self.model, file_name, audio, params)?;
+            let content_type = form.content_type();
+            let mut request = self
+                .agent
+                .post(&self.endpoint)
+                .header("Content-Type", &content_type)
+                .header("Accept", "application/json");
+            if let Some(api_key) = self.params.api_key.as_deref() {
+                request = request.header("Authorization", &format!("Bearer {api_key}"));
+            }
+            for (name, value) in &self.params.headers {
+                request = request.header(name, value);
+            }
+
+            let outcome = request.send(&form.finish()[..]).and_then(|mut response| {
+                let status = response.status();
+                response
+                    .body_mut()
+                    .read_to_string()
+                    .map(|body| (status, body))
+            });
+            let (status, body) = match outcome {
+                Ok(response) => response,
+                Err(error) if is_transient(&error) && attempt < self.params.max_retries => {
+                    std::thread::sleep(retry_delay(self.params.retry_backoff_ms, attempt));
+                    attempt += 1;
+                    continue;
+                }
+                Err(error) => return Err(error.into()),
+            };
+
+            if status.is_success() {
+                let payload: VerboseJsonPayload = serde_json::from_str(&body).map_err(|error| {
+                    io_error(format!("failed to decode transcription response: {error}"))
+                })?;
+                return Ok(payload.into_transcription_result());
+            }
+
+            if status.is_server_error() && attempt < self.params.max_retries {
+                std::thread::sleep(retry_delay(self.params.retry_backoff_ms, attempt));
+                attempt += 1;
+                continue;
+            }
+
+            return Err(io_error(format!(
+                "transcription request failed with HTTP {}: {}",
+                status.as_u16(),
+                body.trim()
+            )));
+        }
+    }
+}
+
+fn transcription_form(
+    model: &str,
+    file_name: &str,
+    audio: &[u8],
+    params: &RemoteInferenceParams,
+) -> Result<MultipartForm, Box<dyn std::error::Error>> {
+    let mut form = MultipartForm::new()
+        .file("file", file_name, "audio/wav", audio)
+        .text("model", model)
+        .text("response_format", "verbose_json");
+
+    if let Some(language) = params.language.as_deref() {
+        form = form.text("language", language);
+    }
+    if let Some(prompt) = params.prompt.as_deref().filter(|prompt| !prompt.is_empty()) {
+        form = form.text("prompt", prompt);
+    }
+    if let Some(temperature) = params.temperature {
+        if !(0.0..=1.0).contains(&temperature) {
+            return Err(io_error(format!(
+                "temperature must be between 0.0 and 1.0, got {temperature}"
+            )));
+        }
+        form = form.text("temperature", &temperature.to_string());
+    }
+    for granularity in &params.timestamp_granularities {
+        form = form.text("timestamp_granularities[]", granularity.as_str());
+    }
+
+    Ok(form)
+}
+
+/// Network failures that may succeed on another attempt, as opposed to bad requests
+/// or TLS and protocol errors.
+fn is_transient(error: &ureq::Error) -> bool {
+    matches!(
+        error,
+        ureq::Error::Io(_)
+            | ureq::Error::Timeout(_)
+            | ureq::Error::ConnectionFailed
+            | ureq::Error::HostNotFound
+    )
+}
+
+fn retry_delay(backoff_ms: u64, attempt: u32) -> Duration {
+    Duration::from_millis(backoff_ms.saturating_mul(1 << attempt.min(16)))
+}
+
+fn io_error(message: impl Into<String>) -> Box<dyn std::error::Error> {
+    std::io::Error::other(message.into()).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{retry_delay, transcription_form, RemoteInferenceParams, TimestampGranularity};
+
+    #[test]
+    fn form_requests_verbose_json_with_granularities() {
+        let params = RemoteInferenceParams {
+            language: Some("en".to_string()),
+            prompt: Some("Glimpse".to_string()),
+            temperature: Some(0.2),
+            timestamp_granularities: vec![
+                TimestampGranularity::Word,
+                TimestampGranularity::Segment,
+            ],
+        };
+        let body = String::from_utf8(
+            transcription_form("whisper-1", "a.wav", b"RIFF", &params)
+                .expect("form should build")
+                .finish(),
+        )
+        .expect("form is utf-8");
+
+        assert!(body.contains("name=\"model\"\r\n\r\nwhisper-1\r\n"));
+        assert!(body.contains("name=\"response_format\"\r\n\r\nverbose_json\r\n"));
+        assert!(body.contains("name=\"prompt\"\r\n\r\nGlimpse\r\n"));
+        assert_eq!(
+            body.matches("name=\"timestamp_granularities[]\"").count(),
+            2
+        );
+    }
+
+    #[test]
+    fn rejects_out_of_range_temperature() {
+        let params = RemoteInferenceParams {
+            temperature: Some(1.5),
+            ..Default::default()
+        };
+        assert!(transcription_form("whisper-1", "a.wav", b"", &params).is_err());
+    }
+
+    #[test]
+    fn retry_delay_doubles() {
+        assert_eq!(retry_delay(100, 0), Duration::from_millis(100));
+        assert_eq!(retry_delay(100, 2), Duration::from_millis(400));
+    }
+}
@@ -1,21 +1,80 @@
 use std::path::{Path, PathBuf};
+use std::sync::{mpsc, Arc};
+use std::thread;
 
-use whisper_rs::{FullParams, SamplingStrategy, WhisperContext, WhisperContextParameters};
+use whisper_rs::{
+    DtwMode, DtwModelPreset, DtwParameters, FullParams, SamplingStrategy, SegmentCallbackData,
+    WhisperContext, WhisperContextParameters,
+};
 
-use crate::{TranscriptionEngine, TranscriptionResult, TranscriptionSegment};
+use crate::vocabulary::Vocabulary;
+use crate::{TranscriptionEngine, TranscriptionResult, TranscriptionSegment, TranscriptionWord};
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct WhisperModelParams {
+    /// Falls back to the CPU when whisper.cpp was built without a GPU backend.
     pub use_gpu: bool,
+    /// Which GPU to use when there are several.
+    pub gpu_device: i32,
+    /// Faster decoding on supported GPUs. Cannot be combined with `dtw`.
+    pub flash_attn: bool,
+    /// Align tokens to the audio with DTW for more accurate word timestamps.
+    pub dtw: Option<DtwAlignment>,
 }
 
 impl Default for WhisperModelParams {
     fn default() -> Self {
-        Self { use_gpu: true }
+        Self {
+            use_gpu: true,
+            gpu_device: 0,
+            flash_attn: false,
+            dtw: None,
+        }
+    }
+}
+
+/// Cross-attention heads used for DTW alignment. The preset must match the model.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DtwAlignment {
+    TinyEn,
+    Tiny,
+    BaseEn,
+    Base,
+    SmallEn,
+    Small,
+    MediumEn,
+    Medium,
+    LargeV1,
+    LargeV2,
+    LargeV3,
+    /// Use every head of the top `n` text layers; works with any model, including fine-tunes.
+    TopMost(i32),
+}
+
+impl DtwAlignment {
+    fn mode(self) -> DtwMode<'static> {
+        let model_preset = match self {
+            DtwAlignment::TinyEn => DtwModelPreset::TinyEn,
+            DtwAlignment::Tiny => DtwModelPreset::Tiny,
+            DtwAlignment::BaseEn => DtwModelPreset::BaseEn,
+            DtwAlignment::Base => DtwModelPreset::Base,
+            DtwAlignment::SmallEn => DtwModelPreset::SmallEn,
+            DtwAlignment::Small => DtwModelPreset::Small,
+            DtwAlignment::MediumEn => DtwModelPreset::MediumEn,
+            DtwAlignment::Medium => DtwModelPreset::Medium,
+            DtwAlignment::LargeV1 => DtwModelPreset::LargeV1,
+            DtwAlignment::LargeV2 => DtwModelPreset::LargeV2,
+            DtwAlignment::LargeV3 => DtwModelPreset::LargeV3,
+            DtwAlignment::TopMost(n_top) => return DtwMode::TopMost { n_top },
+        };
+        DtwMode::ModelPreset { model_preset }
     }
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct WhisperInferenceParams {
     pub language: Option<String>,
     pub translate: bool,
@@ -27,6 +86,24 @@ pub struct WhisperInferenceParams {
     pub suppress_non_speech_tokens: bool,
     pub no_speech_thold: f32,
     pub initial_prompt: Option<String>,
+    /// CPU threads used for decoding; whisper.cpp's default when `None`.
+    pub n_threads: Option<i32>,
+    /// Fill [`TranscriptionResult::words`], aligned with DTW if the model was loaded with it.
+    pub word_timestamps: bool,
+    /// Detect speaker turns and number them in [`TranscriptionSegment::speaker`].
+    /// Needs a tinydiarize model such as `ggml-small.en-tdrz.bin`.
+    pub tdrz_enable: bool,
+    /// Prompt each call with the end of this engine's earlier results (after
+    /// `initial_prompt`), keeping style, casing and names consistent across utterances.
+    /// See [`WhisperEngine::reset_context`].
+    pub carry_context: bool,
+    /// Prompt size limit in tokens for carried context and `vocabulary`; the oldest
+    /// context and the last vocabulary terms are dropped first.
+    pub max_context_tokens: usize,
+    /// Names and terms to bias towards, added to the prompt after `initial_prompt`.
+    pub vocabulary: Vec<String>,
+    /// Also replace near-misses of `vocabulary` terms in the output text.
+    pub correct_vocabulary: bool,
 }
 
 impl Default for WhisperInferenceParams {
@@ -42,14 +119,119 @@ impl Default for WhisperInferenceParams {
             suppress_non_speech_tokens: true,
             no_speech_thold: 0.2,
             initial_prompt: None,
+            n_threads: None,
+            word_timestamps: false,
+            tdrz_enable: false,
+            carry_context: false,
+            max_context_tokens: 224,
+            vocabulary: Vec::new(),
+            correct_vocabulary: false,
         }
     }
 }
 
+/// A loaded Whisper model that can be shared between threads.
+///
+/// Cloning is cheap: every clone and every [`WhisperEngine`] created with
+/// [`WhisperModel::session`] points at the same weights.
+#[derive(Clone)]
+pub struct WhisperModel {
+    context: Arc<WhisperContext>,
+    path: Option<PathBuf>,
+    dtw: bool,
+}
+
+impl WhisperModel {
+    /// Loads a GGML model file. Paths that are not valid UTF-8 are read into memory
+    /// and loaded with [`WhisperModel::load_from_bytes`].
+    pub fn load(
+        model_path: &Path,
+        params: WhisperModelParams,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        let context_params = context_params(&params)?;
+        let context = match model_path.to_str() {
+            Some(model_path_str) => {
+                WhisperContext::new_with_params(model_path_str, context_params)?
+            }
+            None => WhisperContext::new_from_buffer_with_params(
+                &std::fs::read(model_path)?,
+                context_params,
+            )?,
+        };
+        Ok(Self {
+            context: Arc::new(context),
+            path: Some(model_path.to_path_buf()),
+            dtw: params.dtw.is_some(),
+        })
+    }
+
+    /// Loads a GGML model that is already in memory, e.g. after decrypting it.
+    /// The buffer is copied; it can be dropped once this returns.
+    pub fn load_from_bytes(
+        model: &[u8],
+        params: WhisperModelParams,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        let context = WhisperContext::new_from_buffer_with_params(model, context_params(&params)?)?;
+        Ok(Self {
+            context: Arc::new(context),
+            path: None,
+            dtw: params.dtw.is_some(),
+        })
+    }
+
+    /// The model file, if the model was loaded from one.
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    /// Tokens in `text`; `usize::MAX` if it cannot be tokenized, so it never fits a budget.
+    fn count_tokens(&self, text: &str) -> usize {
+        self.context
+            .tokenize(text, self.context.n_text_ctx() as usize)
+            .map_or(usize::MAX, |tokens| tokens.len())
+    }
+
+    /// A new engine with its own decoding state. Sessions can run on different
+    /// threads at the same time.
+    pub fn session(&self) -> Result<WhisperEngine, Box<dyn std::error::Error>> {
+        Ok(WhisperEngine {
+            state: Some(self.context.create_state()?),
+            model: Some(self.clone()),
+            context_tokens: Vec::new(),
+        })
+    }
+}
+
+/// Returned by [`WhisperEngine::backend_info`]; `Display` gives a one-line summary.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct WhisperBackendInfo {
+    /// `cpu`, `cuda`, `vulkan` or `metal`.
+    pub backend: &'static str,
+    pub blas: bool,
+    pub avx: bool,
+    pub avx2: bool,
+    pub fma: bool,
+    pub f16c: bool,
+    /// whisper.cpp's own `whisper_print_system_info` line.
+    pub system_info: String,
+}
+
+impl std::fmt::Display for WhisperBackendInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "backend={} blas={} avx={} avx2={} fma={} f16c={} ({})",
+            self.backend, self.blas, self.avx, self.avx2, self.fma, self.f16c, self.system_info
+        )
+    }
+}
+
 pub struct WhisperEngine {
-    loaded_model_path: Option<PathBuf>,
     state: Option<whisper_rs::WhisperState>,
-    context: Option<whisper_rs::WhisperContext>,
+    model: Option<WhisperModel>,
+    /// Tokens of earlier results, for `carry_context`.
+    context_tokens: Vec<i32>,
 }
 
 impl Default for WhisperEngine {
@@ -61,11 +243,91 @@ impl Default for WhisperEngine {
 impl WhisperEngine {
     pub fn new() -> Self {
         Self {
-            loaded_model_path: None,
             state: None,
-            context: None,
+            model: None,
+            context_tokens: Vec::new(),
+        }
+    }
+
+    /// The loaded model, for creating more sessions on it.
+    pub fn model(&self) -> Option<&WhisperModel> {
+        self.model.as_ref()
+    }
+
+    pub fn load_model_from_bytes(
+        &mut self,
+        model: &[u8],
+        params: WhisperModelParams,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        *self = WhisperModel::load_from_bytes(model, params)?.session()?;
+        Ok(())
+    }
+
+    /// Forgets the text carried over by `carry_context`, e.g. when a new document starts.
+    pub fn reset_context(&mut self) {
+        self.context_tokens.clear();
+    }
+
+    /// Shorthand for [`WhisperModel::session`] on the loaded model.
+    pub fn session(&self) -> Result<Self, Box<dyn std::error::Error>> {
+        self.model
+            .as_ref()
+            .ok_or_else(|| io_error("Model not loaded. Call load_model() first."))?
+            .session()
+    }
+
+    /// The compute backend this build uses and the CPU features whisper.cpp detected.
+    pub fn backend_info() -> WhisperBackendInfo {
+        let system = whisper_rs::SystemInfo::default();
+        let backend = if cfg!(feature = "whisper-cuda") {
+            "cuda"
+        } else if cfg!(feature = "whisper-vulkan") {
+            "vulkan"
+        } else if cfg!(any(
+            feature = "whisper-metal",
+            all(target_os = "macos", feature = "whisper")
+        )) {
+            "metal"
+        } else {
+            "cpu"
+        };
+        WhisperBackendInfo {
+            backend,
+            blas: system.blas,
+            avx: system.avx,
+            avx2: system.avx2,
+            fma: system.fma,
+            f16c: system.f16c,
+            system_info: whisper_rs::print_system_info().trim().to_string(),
         }
     }
+
+    /// Language probabilities for the first 30 s of `samples`, most likely first.
+    pub fn detect_language(
+        &mut self,
+        samples: &[f32],
+        n_threads: usize,
+    ) -> Result<Vec<(String, f32)>, Box<dyn std::error::Error>> {
+        let state = self
+            .state
+            .as_mut()
+            .ok_or_else(|| io_error("Model not loaded. Call load_model() first."))?;
+
+        let n_threads = n_threads.max(1);
+        state.pcm_to_mel(samples, n_threads)?;
+        let (_, probabilities) = state.lang_detect(0, n_threads)?;
+
+        let mut languages = probabilities
+            .into_iter()
+            .enumerate()
+            .filter_map(|(id, probability)| {
+                let language = whisper_rs::get_lang_str(id as i32)?;
+                Some((language.to_string(), probability))
+            })
+            .collect::<Vec<_>>();
+        languages.sort_by(|left, right| right.1.total_cmp(&left.1));
+        Ok(languages)
+    }
 }
 
 impl Drop for WhisperEngine {
@@ -78,32 +340,28 @@ impl TranscriptionEngine for WhisperEngine {
     type InferenceParams = WhisperInferenceParams;
     type ModelParams = WhisperModelParams;
 
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            level = "info",
+            skip_all,
+            fields(engine = "whisper", model = %model_path.display()),
+            err
+        )
+    )]
     fn load_model_with_params(
         &mut self,
         model_path: &Path,
         params: Self::ModelParams,
     ) -> Result<(), Box<dyn std::error::Error>> {
-        let model_path_str = model_path
-            .to_str()
-            .ok_or_else(|| io_error("model path is not valid UTF-8"))?;
-
-        let context_params = WhisperContextParameters {
-            use_gpu: params.use_gpu,
-            ..WhisperContextParameters::default()
-        };
-        let context = WhisperContext::new_with_params(model_path_str, context_params)?;
-        let state = context.create_state()?;
-
-        self.context = Some(context);
-        self.state = Some(state);
-        self.loaded_model_path = Some(model_path.to_path_buf());
+        *self = WhisperModel::load(model_path, params)?.session()?;
         Ok(())
     }
 
     fn unload_model(&mut self) {
-        self.loaded_model_path = None;
         self.state = None;
-        self.context = None;
+        self.model = None;
+        self.context_tokens.clear();
     }
 
     fn transcribe_samples(
@@ -111,57 +369,376 @@ impl TranscriptionEngine for WhisperEngine {
         samples: Vec<f32>,
         params: Option<Self::InferenceParams>,
     ) -> Result<TranscriptionResult, Box<dyn std::error::Error>> {
+        self.transcribe(samples, params, None)
+    }
+}
+
+impl WhisperEngine {
+    /// Like [`TranscriptionEngine::transcribe_samples`], but calls `on_segment` as soon as
+    /// each segment is decoded, e.g. to show text while a long file is still running.
+    /// Streamed segments have no `speaker`; the returned result has the full details.
+    pub fn transcribe_samples_with_callback<F>(
+        &mut self,
+        samples: Vec<f32>,
+        params: Option<WhisperInferenceParams>,
+        mut on_segment: F,
+    ) -> Result<TranscriptionResult, Box<dyn std::error::Error>>
+    where
+        F: FnMut(&TranscriptionSegment),
+    {
+        self.transcribe(samples, params, Some(&mut on_segment))
+    }
+
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            level = "debug",
+            skip_all,
+            fields(engine = "whisper", samples = samples.len()),
+            err
+        )
+    )]
+    fn transcribe(
+        &mut self,
+        samples: Vec<f32>,
+        params: Option<WhisperInferenceParams>,
+        on_segment: Option<&mut dyn FnMut(&TranscriptionSegment)>,
+    ) -> Result<TranscriptionResult, Box<dyn std::error::Error>> {
+        let mut whisper_params = params.unwrap_or_default();
+        let dtw = self.model.as_ref().is_some_and(|model| model.dtw);
+        let vocabulary = Vocabulary::new(&whisper_params.vocabulary);
+        if let Some(model) = self.model.as_ref().filter(|_| !vocabulary.is_empty()) {
+            let initial_prompt = whisper_params.initial_prompt.take().unwrap_or_default();
+            let budget = whisper_params
+                .max_context_tokens
+                .saturating_sub(model.count_tokens(&initial_prompt));
+            let glossary = vocabulary
+                .prompt_within(budget, |prompt| model.count_tokens(&format!(" {prompt}")))
+                .unwrap_or_default();
+            let prompt = format!("{initial_prompt} {glossary}").trim().to_string();
+            whisper_params.initial_prompt = (!prompt.is_empty()).then_some(prompt);
+        }
+        let prompt_tokens = match &self.model {
+            Some(model) if whisper_params.carry_context => {
+                let initial = match whisper_params.initial_prompt.as_deref() {
+                    Some(prompt) => model
+                        .context
+                        .tokenize(prompt, model.context.n_text_ctx() as usize)?,
+                    None => Vec::new(),
+                };
+                carry_over_prompt(
+                    &initial,
+                    &self.context_tokens,
+                    whisper_params.max_context_tokens,
+                )
+            }
+            _ => Vec::new(),
+        };
         let state = self
             .state
             .as_mut()
             .ok_or_else(|| io_error("Model not loaded. Call load_model() first."))?;
 
-        let whisper_params = params.unwrap_or_default();
-
-        let mut full_params = FullParams::new(SamplingStrategy::BeamSearch {
-            beam_size: 3,
-            patience: -1.0,
-        });
-        full_params.set_language(whisper_params.language.as_deref());
-        full_params.set_translate(whisper_params.translate);
-        full_params.set_print_special(whisper_params.print_special);
-        full_params.set_print_progress(whisper_params.print_progress);
-        full_params.set_print_realtime(whisper_params.print_realtime);
-        full_params.set_print_timestamps(whisper_params.print_timestamps);
-        full_params.set_suppress_blank(whisper_params.suppress_blank);
-        full_params.set_suppress_non_speech_tokens(whisper_params.suppress_non_speech_tokens);
-        full_params.set_no_speech_thold(whisper_params.no_speech_thold);
-
-        if let Some(prompt) = whisper_params.initial_prompt.as_deref() {
-            full_params.set_initial_prompt(prompt);
+        match on_segment {
+            Some(on_segment) => decode_streaming(
+                state,
+                &whisper_params,
+                &prompt_tokens,
+                &samples,
+                &vocabulary,
+                on_segment,
+            )?,
+            None => {
+                state.full(full_params(&whisper_params, &prompt_tokens), &samples)?;
+            }
         }
 
-        state.full(full_params, &samples)?;
-
+        let eot = self
+            .model
+            .as_ref()
+            .map_or(i32::MAX, |model| model.context.token_eot());
         let num_segments = state.full_n_segments()?;
         let mut segments = Vec::new();
+        let mut words = Vec::new();
         let mut full_text = String::new();
+        let mut speaker = 0;
+        let mut result_tokens = Vec::new();
 
         for i in 0..num_segments {
-            let text = state.full_get_segment_text(i)?;
+            let mut text = state.full_get_segment_text(i)?;
+            if whisper_params.correct_vocabulary {
+                text = vocabulary.correct(&text);
+            }
             let start = state.full_get_segment_t0(i)? as f32 / 100.0;
             let end = state.full_get_segment_t1(i)? as f32 / 100.0;
 
+            if whisper_params.carry_context {
+                for token in 0..state.full_n_tokens(i)? {
+                    let id = state.full_get_token_id(i, token)?;
+                    if id < eot {
+                        result_tokens.push(id);
+                    }
+                }
+            }
+
+            if whisper_params.word_timestamps {
+                let mut tokens = Vec::new();
+                for token in 0..state.full_n_tokens(i)? {
+                    let data = state.full_get_token_data(i, token)?;
+                    // Timestamp and other special tokens sort after end-of-text.
+                    if data.id >= eot {
+                        continue;
+                    }
+                    let text = state.full_get_token_text_lossy(i, token)?;
+                    let (t0, t1) = if dtw {
+                        (data.t_dtw, data.t_dtw)
+                    } else {
+                        (data.t0, data.t1)
+                    };
+                    tokens.push(TimedToken { text, t0, t1 });
+                }
+                words.extend(group_words(&tokens, end, dtw));
+            }
+
             segments.push(TranscriptionSegment {
                 start,
                 end,
                 text: text.clone(),
+                speaker: whisper_params.tdrz_enable.then_some(speaker),
             });
+            if whisper_params.tdrz_enable && state.full_get_segment_speaker_turn_next(i) {
+                speaker += 1;
+            }
             full_text.push_str(&text);
         }
 
+        if whisper_params.carry_context {
+            self.context_tokens.extend(result_tokens);
+            let excess = self
+                .context_tokens
+                .len()
+                .saturating_sub(whisper_params.max_context_tokens);
+            self.context_tokens.drain(..excess);
+        }
+
         Ok(TranscriptionResult {
             text: full_text.trim().to_string(),
             segments: Some(segments),
+            words: whisper_params.word_timestamps.then_some(words),
+            tokens: None,
+            warnings: Vec::new(),
         })
     }
 }
 
+/// `initial` followed by as much of the most recent `carried` text as fits in `budget`.
+fn carry_over_prompt(initial: &[i32], carried: &[i32], budget: usize) -> Vec<i32> {
+    if initial.len() >= budget {
+        return initial[initial.len() - budget..].to_vec();
+    }
+    let room = budget - initial.len();
+    let carried = &carried[carried.len().saturating_sub(room)..];
+    initial.iter().chain(carried).copied().collect()
+}
+
+/// `prompt_tokens`, when not empty, replace `initial_prompt`, which they already include.
+fn full_params<'a>(
+    whisper_params: &'a WhisperInferenceParams,
+    prompt_tokens: &'a [i32],
+) -> FullParams<'a, 'a> {
+    let mut full_params = FullParams::new(SamplingStrategy::BeamSearch {
+        beam_size: 3,
+        patience: -1.0,
+    });
+    full_params.set_language(whisper_params.language.as_deref());
+    full_params.set_translate(whisper_params.translate);
+    full_params.set_print_special(whisper_params.print_special);
+    full_params.set_print_progress(whisper_params.print_progress);
+    full_params.set_print_realtime(whisper_params.print_realtime);
+    full_params.set_print_timestamps(whisper_params.print_timestamps);
+    full_params.set_suppress_blank(whisper_params.suppress_blank);
+    full_params.set_suppress_non_speech_tokens(whisper_params.suppress_non_speech_tokens);
+    full_params.set_no_speech_thold(whisper_params.no_speech_thold);
+
+    if !prompt_tokens.is_empty() {
+        full_params.set_tokens(prompt_tokens);
+    } else if let Some(prompt) = whisper_params.initial_prompt.as_deref() {
+        full_params.set_initial_prompt(prompt);
+    }
+    if let Some(n_threads) = whisper_params.n_threads {
+        full_params.set_n_threads(n_threads);
+    }
+    full_params.set_token_timestamps(whisper_params.word_timestamps);
+    full_params.set_tdrz_enable(whisper_params.tdrz_enable);
+
+    full_params
+}
+
+/// Runs `state.full` on a helper thread so `on_segment` can run on this one while
+/// decoding continues; whisper-rs only accepts `'static` segment callbacks. Segments get
+/// the same vocabulary correction as the final result.
+fn decode_streaming(
+    state: &mut whisper_rs::WhisperState,
+    whisper_params: &WhisperInferenceParams,
+    prompt_tokens: &[i32],
+    samples: &[f32],
+    vocabulary: &Vocabulary,
+    on_segment: &mut dyn FnMut(&TranscriptionSegment),
+) -> Result<(), Box<dyn std::error::Error>> {
+    let (segments, decoded) = mpsc::channel();
+    thread::scope(|scope| {
+        let decoder = scope.spawn(move || {
+            let mut params = full_params(whisper_params, prompt_tokens);
+            params.set_segment_callback_safe_lossy(move |data: SegmentCallbackData| {
+                let _ = segments.send(TranscriptionSegment {
+                    start: data.start_timestamp as f32 / 100.0,
+                    end: data.end_timestamp as f32 / 100.0,
+                    text: data.text,
+                    speaker: None,
+                });
+            });
+            state
+                .full(params, samples)
+                .map(|_| ())
+                .map_err(|error| error.to_string())
+        });
+        for mut segment in decoded {
+            if whisper_params.correct_vocabulary {
+                segment.text = vocabulary.correct(&segment.text);
+            }
+            on_segment(&segment);
+        }
+        decoder
+            .join()
+            .map_err(|_| io_error("whisper decoding panicked"))?
+            .map_err(io_error)
+    })
+}
+
+fn context_params(
+    params: &WhisperModelParams,
+) -> Result<WhisperContextParameters<'static>, Box<dyn std::error::Error>> {
+    if params.flash_attn && params.dtw.is_some() {
+        return Err(io_error(
+            "flash attention cannot be combined with DTW alignment",
+        ));
+    }
+    Ok(WhisperContextParameters {
+        use_gpu: params.use_gpu,
+        gpu_device: params.gpu_device,
+        flash_attn: params.flash_attn,
+        dtw_parameters: DtwParameters {
+            mode: params.dtw.map_or(DtwMode::None, DtwAlignment::mode),
+            ..DtwParameters::default()
+        },
+    })
+}
+
+struct TimedToken {
+    text: String,
+    /// Centiseconds. With DTW both are the aligned time of the token.
+    t0: i64,
+    t1: i64,
+}
+
+/// Joins BPE tokens into words; a leading space starts a new word. DTW gives one time
+/// per token, so a DTW word ends where the next word starts.
+fn group_words(tokens: &[TimedToken], segment_end: f32, dtw: bool) -> Vec<TranscriptionWord> {
+    let mut words: Vec<TranscriptionWord> = Vec::new();
+    for token in tokens {
+        let starts_word = token.text.starts_with(' ') || words.is_empty();
+        let text = if starts_word {
+            token.text.trim_start()
+        } else {
+            token.text.as_str()
+        };
+        if text.is_empty() {
+            continue;
+        }
+        let (start, end) = (token.t0 as f32 / 100.0, token.t1 as f32 / 100.0);
+        match words.last_mut() {
+            Some(word) if !starts_word => {
+                word.text.push_str(text);
+                word.end = end;
+            }
+            _ => {
+                if dtw {
+                    if let Some(previous) = words.last_mut() {
+                        previous.end = start;
+                    }
+                }
+                words.push(TranscriptionWord {
+                    start,
+                    end,
+                    text: text.to_string(),
+                });
+            }
+        }
+    }
+    if dtw {
+        if let Some(last) = words.last_mut() {
+            last.end = segment_end.max(last.start);
+        }
+    }
+    words
+}
+
 fn io_error(message: impl Into<String>) -> Box<dyn std::error::Error> {
     std::io::Error::other(message.into()).into()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{carry_over_prompt, group_words, TimedToken};
+
+    #[test]
+    fn carried_prompt_keeps_initial_prompt_and_newest_context() {
+        assert_eq!(
+            carry_over_prompt(&[1, 2], &[10, 11, 12, 13], 4),
+            [1, 2, 12, 13]
+        );
+        assert_eq!(carry_over_prompt(&[], &[10, 11], 4), [10, 11]);
+        assert_eq!(carry_over_prompt(&[1, 2, 3, 4, 5], &[10], 3), [3, 4, 5]);
+    }
+
+    fn token(text: &str, t0: i64, t1: i64) -> TimedToken {
+        TimedToken {
+            text: text.to_string(),
+            t0,
+            t1,
+        }
+    }
+
+    #[test]
+    fn joins_subword_tokens_into_words() {
+        let tokens = [
+            token(" Hel", 0, 20),
+            token("lo", 20, 40),
+            token(" world", 50, 90),
+        ];
+
+        let words = group_words(&tokens, 1.0, false);
+
+        assert_eq!(words.len(), 2);
+        assert_eq!(words[0].text, "Hello");
+        assert_eq!((words[0].start, words[0].end), (0.0, 0.4));
+        assert_eq!(words[1].text, "world");
+        assert_eq!((words[1].start, words[1].end), (0.5, 0.9));
+    }
+
+    #[test]
+    fn dtw_words_end_where_the_next_word_starts() {
+        let tokens = [
+            token(" Hi", 10, 10),
+            token(" there", 60, 60),
+            token(".", 80, 80),
+        ];
+
+        let words = group_words(&tokens, 1.2, true);
+
+        assert_eq!(words[0].text, "Hi");
+        assert_eq!((words[0].start, words[0].end), (0.1, 0.6));
+        assert_eq!(words[1].text, "there.");
+        assert_eq!((words[1].start, words[1].end), (0.6, 1.2));
+    }
+}
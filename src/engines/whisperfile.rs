@@ -1,12 +1,20 @@
+use std::io::{BufRead, BufReader};
+use std::net::{SocketAddr, TcpListener, ToSocketAddrs};
 use std::path::{Path, PathBuf};
+use std::process::{Child, ChildStderr, Command, Stdio};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
 
+use crate::format::{parse_subtitles, ResponseFormat};
+use crate::http::{MultipartForm, VerboseJsonPayload};
 use crate::{TranscriptionEngine, TranscriptionResult};
 
-use super::fluid::{
-    FluidEngine, FluidInferenceParams, FluidModelParams, FluidTimestampGranularity,
-};
+const HEALTH_POLL_INTERVAL: Duration = Duration::from_millis(100);
+/// Spawns tried with `port: 0` when another process takes the picked port first.
+const PORT_ATTEMPTS: usize = 5;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum GPUMode {
     #[default]
     Auto,
@@ -29,17 +37,27 @@ impl GPUMode {
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct WhisperfileModelParams {
+    /// Port for the whisperfile server. `0` picks a free port, and picks again if
+    /// whisperfile cannot bind it.
     pub port: u16,
     pub host: String,
     pub startup_timeout_secs: u64,
     pub gpu: GPUMode,
+    #[deprecated(note = "unused: whisperfile runs as its own server process")]
+    #[cfg_attr(feature = "serde", serde(default))]
     pub diarization_model_dir: Option<PathBuf>,
+    #[deprecated(note = "unused: whisperfile runs as its own server process")]
+    #[cfg_attr(feature = "serde", serde(default))]
     pub dylib_path: Option<PathBuf>,
+    #[deprecated(note = "unused: whisperfile runs as its own server process")]
+    #[cfg_attr(feature = "serde", serde(default))]
     pub runtime_macos_major: Option<u32>,
 }
 
 impl Default for WhisperfileModelParams {
+    #[allow(deprecated)]
     fn default() -> Self {
         Self {
             port: 8080,
@@ -54,11 +72,17 @@ impl Default for WhisperfileModelParams {
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct WhisperfileInferenceParams {
     pub language: Option<String>,
     pub translate: bool,
+    /// Sampling temperature in `0.0..=1.0`; the server default when `None`.
     pub temperature: Option<f32>,
+    /// One of `json`, `text`, `srt`, `verbose_json` or `vtt`. Subtitle formats are parsed
+    /// back into segments; `text` and `json` carry no timestamps.
     pub response_format: Option<String>,
+    /// Sent as the server's `prompt` field, followed by the `vocabulary` terms.
+    pub prompt: Option<String>,
     pub vocabulary: Vec<String>,
 }
 
@@ -69,25 +93,30 @@ impl Default for WhisperfileInferenceParams {
             translate: false,
             temperature: None,
             response_format: Some("verbose_json".to_string()),
+            prompt: None,
             vocabulary: Vec::new(),
         }
     }
 }
 
-/// Compatibility Whisperfile API, but executes through the FluidAudio bridge.
+/// Runs a whisperfile/llamafile binary as a local server and transcribes through its HTTP API.
 pub struct WhisperfileEngine {
-    #[allow(dead_code)]
     binary_path: PathBuf,
-    inner: FluidEngine,
+    server: Option<WhisperfileServer>,
 }
 
 impl WhisperfileEngine {
     pub fn new(binary_path: impl Into<PathBuf>) -> Self {
         Self {
             binary_path: binary_path.into(),
-            inner: FluidEngine::new(),
+            server: None,
         }
     }
+
+    /// Base URL of the running server, e.g. `http://127.0.0.1:8080`.
+    pub fn server_url(&self) -> Option<&str> {
+        self.server.as_ref().map(|server| server.base_url.as_str())
+    }
 }
 
 impl Default for WhisperfileEngine {
@@ -96,6 +125,12 @@ impl Default for WhisperfileEngine {
     }
 }
 
+impl Drop for WhisperfileEngine {
+    fn drop(&mut self) {
+        self.unload_model();
+    }
+}
+
 impl TranscriptionEngine for WhisperfileEngine {
     type InferenceParams = WhisperfileInferenceParams;
     type ModelParams = WhisperfileModelParams;
@@ -105,32 +140,23 @@ impl TranscriptionEngine for WhisperfileEngine {
         model_path: &Path,
         params: Self::ModelParams,
     ) -> Result<(), Box<dyn std::error::Error>> {
-        let _ = (
-            &params.port,
-            &params.host,
-            &params.startup_timeout_secs,
-            &params.gpu,
-        );
-        let effective_model_path = if model_path.is_file() {
-            model_path
-                .parent()
-                .ok_or_else(|| std::io::Error::other("model file path has no parent directory"))?
-        } else {
-            model_path
-        };
+        if !model_path.is_file() {
+            return Err(io_error(format!(
+                "Model file not found: {}",
+                model_path.display()
+            )));
+        }
 
-        self.inner.load_model_with_params(
-            effective_model_path,
-            FluidModelParams {
-                diarization_model_dir: params.diarization_model_dir,
-                dylib_path: params.dylib_path,
-                runtime_macos_major: params.runtime_macos_major,
-            },
-        )
+        self.unload_model();
+        let server = WhisperfileServer::start(&self.binary_path, model_path, &params)?;
+        self.server = Some(server);
+        Ok(())
     }
 
     fn unload_model(&mut self) {
-        self.inner.unload_model();
+        if let Some(mut server) = self.server.take() {
+            server.shutdown();
+        }
     }
 
     fn transcribe_samples(
@@ -138,8 +164,13 @@ impl TranscriptionEngine for WhisperfileEngine {
         samples: Vec<f32>,
         params: Option<Self::InferenceParams>,
     ) -> Result<TranscriptionResult, Box<dyn std::error::Error>> {
-        self.inner
-            .transcribe_samples(samples, Some(map_inference_params(params)))
+        let server = self
+            .server
+            .as_mut()
+            .ok_or_else(|| io_error("Model not loaded. Call load_model() first."))?;
+
+        let wav = crate::audio::encode_wav_samples(&samples)?;
+        server.inference("audio.wav", &wav, &params.unwrap_or_default())
     }
 
     fn transcribe_file(
@@ -147,19 +178,409 @@ impl TranscriptionEngine for WhisperfileEngine {
         wav_path: &Path,
         params: Option<Self::InferenceParams>,
     ) -> Result<TranscriptionResult, Box<dyn std::error::Error>> {
-        self.inner
-            .transcribe_file(wav_path, Some(map_inference_params(params)))
+        let server = self
+            .server
+            .as_mut()
+            .ok_or_else(|| io_error("Model not loaded. Call load_model() first."))?;
+
+        let audio = std::fs::read(wav_path).map_err(|error| {
+            io_error(format!(
+                "failed to read audio file {}: {error}",
+                wav_path.display()
+            ))
+        })?;
+        let file_name = wav_path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "audio.wav".to_string());
+
+        server.inference(&file_name, &audio, &params.unwrap_or_default())
+    }
+}
+
+struct WhisperfileServer {
+    child: Child,
+    base_url: String,
+    agent: ureq::Agent,
+    /// Forwards the server's stderr; yields whether it reported a port bind failure.
+    stderr: Option<JoinHandle<bool>>,
+}
+
+enum StartFailure {
+    PortInUse(Box<dyn std::error::Error>),
+    Other(Box<dyn std::error::Error>),
+}
+
+impl WhisperfileServer {
+    fn start(
+        binary_path: &Path,
+        model_path: &Path,
+        params: &WhisperfileModelParams,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        let mut attempt = 1;
+        loop {
+            // `free_port` releases the port before whisperfile binds it, so it can be lost
+            // to another process in between.
+            let port = match params.port {
+                0 => free_port(&params.host)?,
+                port => port,
+            };
+            match Self::start_on_port(binary_path, model_path, params, port) {
+                Ok(server) => return Ok(server),
+                Err(StartFailure::PortInUse(_)) if params.port == 0 && attempt < PORT_ATTEMPTS => {
+                    attempt += 1;
+                }
+                Err(StartFailure::PortInUse(error) | StartFailure::Other(error)) => {
+                    return Err(error)
+                }
+            }
+        }
+    }
+
+    fn start_on_port(
+        binary_path: &Path,
+        model_path: &Path,
+        params: &WhisperfileModelParams,
+        port: u16,
+    ) -> Result<Self, StartFailure> {
+        let mut child = Command::new(binary_path)
+            .arg("--server")
+            .arg("-m")
+            .arg(model_path)
+            .arg("--host")
+            .arg(&params.host)
+            .arg("--port")
+            .arg(port.to_string())
+            .arg("--gpu")
+            .arg(params.gpu.as_arg())
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|error| {
+                StartFailure::Other(io_error(format!(
+                    "failed to start whisperfile binary {}: {error}",
+                    binary_path.display()
+                )))
+            })?;
+        let stderr = child
+            .stderr
+            .take()
+            .map(|stderr| std::thread::spawn(move || forward_stderr(stderr)));
+
+        let agent = ureq::Agent::new_with_config(
+            ureq::Agent::config_builder()
+                .http_status_as_error(false)
+                .build(),
+        );
+
+        let mut server = Self {
+            child,
+            base_url: format!("http://{}", format_host_port(&params.host, port)),
+            agent,
+            stderr,
+        };
+
+        if let Err(error) =
+            server.wait_until_healthy(Duration::from_secs(params.startup_timeout_secs))
+        {
+            let exited = matches!(server.child.try_wait(), Ok(Some(_)));
+            server.shutdown();
+            let port_in_use = exited
+                && server
+                    .stderr
+                    .take()
+                    .is_some_and(|stderr| stderr.join().unwrap_or(false));
+            return Err(if port_in_use {
+                StartFailure::PortInUse(error)
+            } else {
+                StartFailure::Other(error)
+            });
+        }
+
+        Ok(server)
+    }
+
+    fn wait_until_healthy(&mut self, timeout: Duration) -> Result<(), Box<dyn std::error::Error>> {
+        let deadline = Instant::now() + timeout;
+        let health_url = format!("{}/health", self.base_url);
+
+        loop {
+            if let Some(status) = self.child.try_wait()? {
+                return Err(io_error(format!(
+                    "whisperfile exited during startup ({status})"
+                )));
+            }
+
+            let probe = self
+                .agent
+                .get(&health_url)
+                .config()
+                .timeout_global(Some(HEALTH_POLL_INTERVAL * 5))
+                .build()
+                .call();
+            if let Ok(response) = probe {
+                // Once our process has exited, whoever answers is another server on the port.
+                if response.status().is_success() && self.child.try_wait()?.is_none() {
+                    return Ok(());
+                }
+            }
+
+            if Instant::now() >= deadline {
+                return Err(io_error(format!(
+                    "whisperfile did not become healthy at {health_url} within {}s",
+                    timeout.as_secs()
+                )));
+            }
+            std::thread::sleep(HEALTH_POLL_INTERVAL);
+        }
+    }
+
+    fn inference(
+        &mut self,
+        file_name: &str,
+        audio: &[u8],
+        params: &WhisperfileInferenceParams,
+    ) -> Result<TranscriptionResult, Box<dyn std::error::Error>> {
+        if let Some(status) = self.child.try_wait()? {
+            return Err(io_error(format!("whisperfile server exited ({status})")));
+        }
+
+        let (form, response_format) = inference_form(file_name, audio, params)?;
+
+        let content_type = form.content_type();
+        let mut response = self
+            .agent
+            .post(&format!("{}/inference", self.base_url))
+            .header("Content-Type", &content_type)
+            .send(&form.finish()[..])?;
+
+        let status = response.status();
+        let body = response.body_mut().read_to_string()?;
+        if !status.is_success() {
+            return Err(io_error(format!(
+                "whisperfile inference failed with HTTP {}: {}",
+                status.as_u16(),
+                body.trim()
+            )));
+        }
+
+        parse_inference_response(&body, response_format)
+    }
+
+    fn shutdown(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+fn inference_form(
+    file_name: &str,
+    audio: &[u8],
+    params: &WhisperfileInferenceParams,
+) -> Result<(MultipartForm, ResponseFormat), Box<dyn std::error::Error>> {
+    let response_format = match params.response_format.as_deref() {
+        Some(value) => value.parse::<ResponseFormat>()?,
+        None => ResponseFormat::VerboseJson,
+    };
+
+    let mut form = MultipartForm::new()
+        .file("file", file_name, "audio/wav", audio)
+        .text("response_format", response_format.as_str())
+        .text("translate", if params.translate { "true" } else { "false" });
+
+    if let Some(language) = params.language.as_deref() {
+        form = form.text("language", language);
+    }
+
+    if let Some(temperature) = params.temperature {
+        if !(0.0..=1.0).contains(&temperature) {
+            return Err(io_error(format!(
+                "temperature must be between 0.0 and 1.0, got {temperature}"
+            )));
+        }
+        form = form.text("temperature", &temperature.to_string());
+    }
+
+    let vocabulary = params
+        .vocabulary
+        .iter()
+        .map(|term| term.trim())
+        .filter(|term| !term.is_empty())
+        .collect::<Vec<_>>();
+    let prompt = [
+        params
+            .prompt
+            .as_deref()
+            .unwrap_or_default()
+            .trim()
+            .to_string(),
+        vocabulary.join(", "),
+    ]
+    .into_iter()
+    .filter(|part| !part.is_empty())
+    .collect::<Vec<_>>()
+    .join(" ");
+    if !prompt.is_empty() {
+        form = form.text("prompt", &prompt);
+    }
+
+    Ok((form, response_format))
+}
+
+fn parse_inference_response(
+    body: &str,
+    response_format: ResponseFormat,
+) -> Result<TranscriptionResult, Box<dyn std::error::Error>> {
+    match response_format {
+        ResponseFormat::Json | ResponseFormat::VerboseJson => {
+            let payload: VerboseJsonPayload = serde_json::from_str(body).map_err(|error| {
+                io_error(format!("failed to decode whisperfile response: {error}"))
+            })?;
+            Ok(payload.into_transcription_result())
+        }
+        ResponseFormat::Text => Ok(TranscriptionResult {
+            text: body.trim().to_string(),
+            ..Default::default()
+        }),
+        ResponseFormat::Srt | ResponseFormat::Vtt => {
+            let segments = parse_subtitles(body)?;
+            let text = segments
+                .iter()
+                .map(|segment| segment.text.trim())
+                .filter(|text| !text.is_empty())
+                .collect::<Vec<_>>()
+                .join(" ");
+
+            Ok(TranscriptionResult {
+                text,
+                segments: if segments.is_empty() {
+                    None
+                } else {
+                    Some(segments)
+                },
+                words: None,
+                tokens: None,
+                warnings: Vec::new(),
+            })
+        }
     }
 }
 
-fn map_inference_params(params: Option<WhisperfileInferenceParams>) -> FluidInferenceParams {
-    let params = params.unwrap_or_default();
+/// Copies whisperfile's stderr to ours and reports whether it failed to bind its port.
+fn forward_stderr(stderr: ChildStderr) -> bool {
+    let mut reader = BufReader::new(stderr);
+    let mut line = Vec::new();
+    let mut port_in_use = false;
+    while matches!(reader.read_until(b'\n', &mut line), Ok(read) if read > 0) {
+        let text = String::from_utf8_lossy(&line);
+        let lowercase = text.to_lowercase();
+        port_in_use |=
+            lowercase.contains("address already in use") || lowercase.contains("couldn't bind");
+        eprint!("{text}");
+        line.clear();
+    }
+    port_in_use
+}
 
-    let _ = (params.translate, params.temperature, params.response_format);
+fn free_port(host: &str) -> Result<u16, Box<dyn std::error::Error>> {
+    let address: SocketAddr = (host, 0)
+        .to_socket_addrs()?
+        .next()
+        .ok_or_else(|| io_error(format!("could not resolve host {host}")))?;
+    Ok(TcpListener::bind(address)?.local_addr()?.port())
+}
 
-    FluidInferenceParams {
-        language: params.language,
-        vocabulary: params.vocabulary,
-        timestamp_granularity: FluidTimestampGranularity::WordPreferred,
+fn format_host_port(host: &str, port: u16) -> String {
+    if host.contains(':') {
+        format!("[{host}]:{port}")
+    } else {
+        format!("{host}:{port}")
+    }
+}
+
+fn io_error(message: impl Into<String>) -> Box<dyn std::error::Error> {
+    std::io::Error::other(message.into()).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        format_host_port, inference_form, parse_inference_response, ResponseFormat,
+        WhisperfileInferenceParams,
+    };
+
+    #[test]
+    fn ipv6_hosts_are_bracketed() {
+        assert_eq!(format_host_port("127.0.0.1", 8080), "127.0.0.1:8080");
+        assert_eq!(format_host_port("::1", 8080), "[::1]:8080");
+    }
+
+    #[test]
+    fn rejects_unknown_response_format_and_out_of_range_temperature() {
+        let bad_format = WhisperfileInferenceParams {
+            response_format: Some("csv".to_string()),
+            ..Default::default()
+        };
+        let error = inference_form("a.wav", b"", &bad_format)
+            .err()
+            .expect("csv must be rejected");
+        assert!(error.to_string().contains("'csv' is unsupported"));
+
+        let bad_temperature = WhisperfileInferenceParams {
+            temperature: Some(1.5),
+            ..Default::default()
+        };
+        assert!(inference_form("a.wav", b"", &bad_temperature).is_err());
+    }
+
+    #[test]
+    fn form_carries_translate_temperature_and_format() {
+        let params = WhisperfileInferenceParams {
+            translate: true,
+            temperature: Some(0.2),
+            response_format: Some("srt".to_string()),
+            ..Default::default()
+        };
+        let (form, format) = inference_form("a.wav", b"RIFF", &params).expect("valid params");
+        let body = String::from_utf8(form.finish()).expect("form is utf-8");
+
+        assert_eq!(format, ResponseFormat::Srt);
+        assert!(body.contains("name=\"translate\"\r\n\r\ntrue\r\n"));
+        assert!(body.contains("name=\"temperature\"\r\n\r\n0.2\r\n"));
+        assert!(body.contains("name=\"response_format\"\r\n\r\nsrt\r\n"));
+    }
+
+    #[test]
+    fn form_prompt_keeps_free_text_apart_from_vocabulary() {
+        let params = WhisperfileInferenceParams {
+            prompt: Some("Meeting notes, part two.".to_string()),
+            vocabulary: vec!["Glimpse".to_string(), " Kubernetes ".to_string()],
+            ..Default::default()
+        };
+        let (form, _) = inference_form("a.wav", b"RIFF", &params).expect("valid params");
+        let body = String::from_utf8(form.finish()).expect("form is utf-8");
+
+        assert!(body
+            .contains("name=\"prompt\"\r\n\r\nMeeting notes, part two. Glimpse, Kubernetes\r\n"));
+    }
+
+    #[test]
+    fn parses_each_response_format() {
+        let text = parse_inference_response(" hi there\n", ResponseFormat::Text).expect("text");
+        assert_eq!(text.text, "hi there");
+        assert_eq!(text.segments, None);
+
+        let json =
+            parse_inference_response(r#"{"text":" hi"}"#, ResponseFormat::Json).expect("json");
+        assert_eq!(json.text, "hi");
+
+        let vtt = parse_inference_response(
+            "WEBVTT\n\n00:00:00.000 --> 00:00:01.000\n hi\n\n00:00:01.000 --> 00:00:02.000\n there\n",
+            ResponseFormat::Vtt,
+        )
+        .expect("vtt");
+        assert_eq!(vtt.text, "hi there");
+        assert_eq!(vtt.segments.map(|segments| segments.len()), Some(2));
     }
 }
@@ -0,0 +1,260 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::TranscriptionSegment;
+
+/// Response formats of the OpenAI-style transcription API (`response_format`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum ResponseFormat {
+    Json,
+    Text,
+    Srt,
+    #[default]
+    VerboseJson,
+    Vtt,
+}
+
+impl ResponseFormat {
+    pub const ALL: [ResponseFormat; 5] = [
+        ResponseFormat::Json,
+        ResponseFormat::Text,
+        ResponseFormat::Srt,
+        ResponseFormat::VerboseJson,
+        ResponseFormat::Vtt,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ResponseFormat::Json => "json",
+            ResponseFormat::Text => "text",
+            ResponseFormat::Srt => "srt",
+            ResponseFormat::VerboseJson => "verbose_json",
+            ResponseFormat::Vtt => "vtt",
+        }
+    }
+}
+
+impl fmt::Display for ResponseFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ResponseFormat {
+    type Err = UnsupportedResponseFormat;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let normalized = value.trim().to_ascii_lowercase();
+        Self::ALL
+            .into_iter()
+            .find(|format| format.as_str() == normalized)
+            .ok_or_else(|| UnsupportedResponseFormat(value.to_string()))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnsupportedResponseFormat(pub String);
+
+impl fmt::Display for UnsupportedResponseFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "response_format '{}' is unsupported by this backend (expected one of json, text, srt, verbose_json, vtt)",
+            self.0
+        )
+    }
+}
+
+impl std::error::Error for UnsupportedResponseFormat {}
+
+/// Parses SRT or WebVTT cues into segments. Cue settings and styling are ignored.
+pub fn parse_subtitles(
+    input: &str,
+) -> Result<Vec<TranscriptionSegment>, Box<dyn std::error::Error>> {
+    let normalized = input.replace("\r\n", "\n");
+    let mut segments = Vec::new();
+
+    for block in normalized.split("\n\n") {
+        let mut lines = block.lines().map(str::trim).filter(|line| !line.is_empty());
+        let Some(mut line) = lines.next() else {
+            continue;
+        };
+
+        if !line.contains("-->") {
+            // Cue identifier (SRT index, optional VTT id) or a header block.
+            match lines.next() {
+                Some(next) if next.contains("-->") => line = next,
+                _ => continue,
+            }
+        }
+
+        let (start, end) = parse_cue_timing(line)?;
+        let text = lines.collect::<Vec<_>>().join(" ");
+        segments.push(TranscriptionSegment {
+            start,
+            end,
+            text,
+            speaker: None,
+        });
+    }
+
+    Ok(segments)
+}
+
+/// Renders segments as SRT cues.
+pub fn render_srt(segments: &[TranscriptionSegment]) -> String {
+    let mut output = String::new();
+    for (index, segment) in segments.iter().enumerate() {
+        output.push_str(&format!(
+            "{}\n{} --> {}\n{}\n\n",
+            index + 1,
+            format_timestamp(segment.start, ','),
+            format_timestamp(segment.end, ','),
+            segment.text.trim()
+        ));
+    }
+    output
+}
+
+/// Renders segments as a WebVTT document.
+pub fn render_vtt(segments: &[TranscriptionSegment]) -> String {
+    let mut output = String::from("WEBVTT\n\n");
+    for segment in segments {
+        output.push_str(&format!(
+            "{} --> {}\n{}\n\n",
+            format_timestamp(segment.start, '.'),
+            format_timestamp(segment.end, '.'),
+            segment.text.trim()
+        ));
+    }
+    output
+}
+
+/// Renders segments as tab-separated `start`, `end` (milliseconds) and `text` rows.
+pub fn render_tsv(segments: &[TranscriptionSegment]) -> String {
+    let mut output = String::from("start\tend\ttext\n");
+    for segment in segments {
+        output.push_str(&format!(
+            "{}\t{}\t{}\n",
+            (segment.start.max(0.0) * 1000.0).round() as u64,
+            (segment.end.max(0.0) * 1000.0).round() as u64,
+            segment.text.trim().replace(['\t', '\n'], " ")
+        ));
+    }
+    output
+}
+
+fn format_timestamp(seconds: f32, fraction_separator: char) -> String {
+    let total_millis = (seconds.max(0.0) as f64 * 1000.0).round() as u64;
+    let hours = total_millis / 3_600_000;
+    let minutes = total_millis / 60_000 % 60;
+    let secs = total_millis / 1000 % 60;
+    let millis = total_millis % 1000;
+    format!("{hours:02}:{minutes:02}:{secs:02}{fraction_separator}{millis:03}")
+}
+
+fn parse_cue_timing(line: &str) -> Result<(f32, f32), Box<dyn std::error::Error>> {
+    let (start, rest) = line
+        .split_once("-->")
+        .ok_or_else(|| invalid_timestamp(line))?;
+    let end = rest.split_whitespace().next().unwrap_or_default();
+
+    Ok((parse_timestamp(start.trim())?, parse_timestamp(end)?))
+}
+
+fn parse_timestamp(value: &str) -> Result<f32, Box<dyn std::error::Error>> {
+    let value = value.replace(',', ".");
+    let parts = value.split(':').collect::<Vec<_>>();
+    let (hours, minutes, seconds) = match parts.as_slice() {
+        [hours, minutes, seconds] => (*hours, *minutes, *seconds),
+        [minutes, seconds] => ("0", *minutes, *seconds),
+        _ => return Err(invalid_timestamp(&value)),
+    };
+
+    let hours: f32 = hours.parse().map_err(|_| invalid_timestamp(&value))?;
+    let minutes: f32 = minutes.parse().map_err(|_| invalid_timestamp(&value))?;
+    let seconds: f32 = seconds.parse().map_err(|_| invalid_timestamp(&value))?;
+    Ok(hours * 3600.0 + minutes * 60.0 + seconds)
+}
+
+fn invalid_timestamp(value: &str) -> Box<dyn std::error::Error> {
+    std::io::Error::other(format!("invalid subtitle timestamp: {value}")).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_subtitles, render_srt, render_tsv, render_vtt, ResponseFormat};
+    use crate::TranscriptionSegment;
+
+    #[test]
+    fn response_format_wire_values_round_trip() {
+        for format in ResponseFormat::ALL {
+            assert_eq!(format.as_str().parse::<ResponseFormat>(), Ok(format));
+        }
+        assert_eq!(
+            " VERBOSE_JSON ".parse::<ResponseFormat>(),
+            Ok(ResponseFormat::VerboseJson)
+        );
+        assert!("csv".parse::<ResponseFormat>().is_err());
+    }
+
+    #[test]
+    fn parses_srt_cues() {
+        let srt = "1\r\n00:00:00,000 --> 00:00:01,500\r\nHello there.\r\n\r\n2\r\n00:00:01,500 --> 00:01:02,250\r\nGeneral\r\nKenobi.\r\n";
+        let segments = parse_subtitles(srt).expect("srt should parse");
+
+        assert_eq!(segments.len(), 2);
+        assert_eq!(segments[0].start, 0.0);
+        assert_eq!(segments[0].end, 1.5);
+        assert_eq!(segments[1].end, 62.25);
+        assert_eq!(segments[1].text, "General Kenobi.");
+    }
+
+    #[test]
+    fn parses_vtt_cues_with_header_and_settings() {
+        let vtt = "WEBVTT\n\nintro\n00:01.000 --> 00:02.500 align:start\nHi\n\n00:00:03.000 --> 00:00:04.000\nBye\n";
+        let segments = parse_subtitles(vtt).expect("vtt should parse");
+
+        assert_eq!(segments.len(), 2);
+        assert_eq!(segments[0].start, 1.0);
+        assert_eq!(segments[0].end, 2.5);
+        assert_eq!(segments[0].text, "Hi");
+        assert_eq!(segments[1].text, "Bye");
+    }
+
+    #[test]
+    fn rendered_subtitles_parse_back() {
+        let segments = vec![
+            TranscriptionSegment {
+                start: 0.0,
+                end: 1.5,
+                text: " Hello there.".to_string(),
+                speaker: None,
+            },
+            TranscriptionSegment {
+                start: 3661.25,
+                end: 3662.0,
+                text: "Bye".to_string(),
+                speaker: None,
+            },
+        ];
+
+        let srt = render_srt(&segments);
+        assert!(srt.starts_with("1\n00:00:00,000 --> 00:00:01,500\nHello there.\n"));
+        assert!(srt.contains("01:01:01,250 --> 01:01:02,000"));
+        assert_eq!(parse_subtitles(&srt).expect("srt should parse").len(), 2);
+
+        let vtt = render_vtt(&segments);
+        assert!(vtt.starts_with("WEBVTT\n\n00:00:00.000 --> 00:00:01.500\n"));
+        let parsed = parse_subtitles(&vtt).expect("vtt should parse");
+        assert_eq!(parsed[1].start, 3661.25);
+        assert_eq!(parsed[0].text, "Hello there.");
+
+        assert_eq!(
+            render_tsv(&segments),
+            "start\tend\ttext\n0\t1500\tHello there.\n3661250\t3662000\tBye\n"
+        );
+    }
+}
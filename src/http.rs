@@ -0,0 +1,218 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::Deserialize;
+
+use crate::{TranscriptionResult, TranscriptionSegment, TranscriptionWord};
+
+static BOUNDARY_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Minimal `multipart/form-data` body builder for the HTTP-backed engines.
+pub(crate) struct MultipartForm {
+    boundary: String,
+    body: Vec<u8>,
+}
+
+impl MultipartForm {
+    pub(crate) fn new() -> Self {
+        let counter = BOUNDARY_COUNTER.fetch_add(1, Ordering::Relaxed);
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_nanos())
+            .unwrap_or(0);
+
+        Self {
+            boundary: format!("glimpse-speech-{nanos:x}-{counter}"),
+            body: Vec::new(),
+        }
+    }
+
+    pub(crate) fn text(mut self, name: &str, value: &str) -> Self {
+        self.start_part(&format!("form-data; name=\"{name}\""), None);
+        self.body.extend_from_slice(value.as_bytes());
+        self.body.extend_from_slice(b"\r\n");
+        self
+    }
+
+    pub(crate) fn file(
+        mut self,
+        name: &str,
+        file_name: &str,
+        content_type: &str,
+        bytes: &[u8],
+    ) -> Self {
+        self.start_part(
+            &format!(
+                "form-data; name=\"{name}\"; filename=\"{}\"",
+                quoted_string_content(file_name)
+            ),
+            Some(content_type),
+        );
+        self.body.extend_from_slice(bytes);
+        self.body.extend_from_slice(b"\r\n");
+        self
+    }
+
+    pub(crate) fn content_type(&self) -> String {
+        format!("multipart/form-data; boundary={}", self.boundary)
+    }
+
+    pub(crate) fn finish(mut self) -> Vec<u8> {
+        self.body
+            .extend_from_slice(format!("--{}--\r\n", self.boundary).as_bytes());
+        self.body
+    }
+
+    fn start_part(&mut self, disposition: &str, content_type: Option<&str>) {
+        self.body
+            .extend_from_slice(format!("--{}\r\n", self.boundary).as_bytes());
+        self.body
+            .extend_from_slice(format!("Content-Disposition: {disposition}\r\n").as_bytes());
+        if let Some(content_type) = content_type {
+            self.body
+                .extend_from_slice(format!("Content-Type: {content_type}\r\n").as_bytes());
+        }
+        self.body.extend_from_slice(b"\r\n");
+    }
+}
+
+/// Escapes `"` and `\` and drops CR/LF, so the value stays inside its header quotes.
+fn quoted_string_content(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for character in value.chars() {
+        match character {
+            '\r' | '\n' => {}
+            '"' | '\\' => {
+                escaped.push('\\');
+                escaped.push(character);
+            }
+            _ => escaped.push(character),
+        }
+    }
+    escaped
+}
+
+/// `json` / `verbose_json` body of an OpenAI-style transcription response.
+#[derive(Debug, Deserialize)]
+pub(crate) struct VerboseJsonPayload {
+    text: String,
+    #[serde(default)]
+    segments: Vec<VerboseJsonSegment>,
+    #[serde(default)]
+    words: Vec<VerboseJsonWord>,
+}
+
+#[derive(Debug, Deserialize)]
+struct VerboseJsonSegment {
+    start: f32,
+    end: f32,
+    text: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct VerboseJsonWord {
+    start: f32,
+    end: f32,
+    word: String,
+}
+
+impl VerboseJsonPayload {
+    pub(crate) fn into_transcription_result(self) -> TranscriptionResult {
+        let segments = self
+            .segments
+            .into_iter()
+            .map(|segment| TranscriptionSegment {
+                start: segment.start,
+                end: segment.end,
+                text: segment.text,
+                speaker: None,
+            })
+            .collect::<Vec<_>>();
+        let words = self
+            .words
+            .into_iter()
+            .map(|word| TranscriptionWord {
+                start: word.start,
+                end: word.end,
+                text: word.word.trim().to_string(),
+            })
+            .collect::<Vec<_>>();
+
+        TranscriptionResult {
+            text: self.text.trim().to_string(),
+            segments: if segments.is_empty() {
+                None
+            } else {
+                Some(segments)
+            },
+            words: if words.is_empty() { None } else { Some(words) },
+            tokens: None,
+            warnings: Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MultipartForm, VerboseJsonPayload};
+
+    #[test]
+    fn multipart_form_frames_fields_with_boundary() {
+        let form = MultipartForm::new().text("model", "whisper-1").file(
+            "file",
+            "a.wav",
+            "audio/wav",
+            b"RIFF",
+        );
+        let content_type = form.content_type();
+        let boundary = content_type
+            .strip_prefix("multipart/form-data; boundary=")
+            .expect("content type carries boundary")
+            .to_string();
+        let body = String::from_utf8(form.finish()).expect("form is utf-8");
+
+        assert!(body.starts_with(&format!("--{boundary}\r\n")));
+        assert!(
+            body.contains("Content-Disposition: form-data; name=\"model\"\r\n\r\nwhisper-1\r\n")
+        );
+        assert!(body.contains("filename=\"a.wav\"\r\nContent-Type: audio/wav\r\n\r\nRIFF\r\n"));
+        assert!(body.ends_with(&format!("--{boundary}--\r\n")));
+    }
+
+    #[test]
+    fn multipart_file_name_cannot_break_out_of_its_header() {
+        let form =
+            MultipartForm::new().file("file", "a\"b\\c.wav\r\nX-Injected: 1", "audio/wav", b"RIFF");
+        let body = String::from_utf8(form.finish()).expect("form is utf-8");
+
+        assert!(body
+            .contains("filename=\"a\\\"b\\\\c.wavX-Injected: 1\"\r\nContent-Type: audio/wav\r\n"));
+        assert!(!body.contains("\r\nX-Injected"));
+    }
+
+    #[test]
+    fn parses_verbose_json_segments_and_words() {
+        let json = r#"{"task":"transcribe","language":"english","duration":1.5,"text":" hello world","segments":[{"id":0,"seek":0,"start":0.0,"end":1.5,"text":" hello world","tokens":[1,2],"temperature":0.0}],"words":[{"word":" hello","start":0.0,"end":0.6},{"word":"world","start":0.7,"end":1.5}]}"#;
+        let payload: VerboseJsonPayload = serde_json::from_str(json).expect("valid payload");
+        let result = payload.into_transcription_result();
+
+        assert_eq!(result.text, "hello world");
+        let segments = result.segments.expect("segments should be present");
+        assert_eq!(segments.len(), 1);
+        assert_eq!(segments[0].end, 1.5);
+        let words = result.words.expect("words should be present");
+        assert_eq!(words[0].text, "hello");
+        assert_eq!(words[1].start, 0.7);
+    }
+
+    #[test]
+    fn plain_json_has_no_timestamps() {
+        let payload: VerboseJsonPayload =
+            serde_json::from_str(r#"{"text":"hi"}"#).expect("valid payload");
+        let result = payload.into_transcription_result();
+
+        assert_eq!(result.text, "hi");
+        assert_eq!(result.segments, None);
+        assert_eq!(result.words, None);
+    }
+}
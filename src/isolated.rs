@@ -0,0 +1,519 @@
+use std::fmt;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::marker::PhantomData;
+use std::path::{Path, PathBuf};
+use std::process::{Child, ChildStdin, Command, ExitStatus, Stdio};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::time::Duration;
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::{TranscriptionEngine, TranscriptionResult};
+
+/// Environment variable that switches a binary into worker mode.
+pub const WORKER_ENV_VAR: &str = "GLIMPSE_SPEECH_WORKER";
+
+const MAX_FRAME_BYTES: usize = 1 << 30;
+
+#[derive(Debug, Clone)]
+pub struct IsolatedEngineOptions {
+    /// Executable started as the worker. Defaults to the current executable.
+    pub worker_program: Option<PathBuf>,
+    pub worker_args: Vec<String>,
+    /// Upper bound for a single transcription request.
+    pub request_timeout: Duration,
+    /// Upper bound for loading (or re-loading after a restart) the model.
+    pub load_timeout: Duration,
+}
+
+impl Default for IsolatedEngineOptions {
+    fn default() -> Self {
+        Self {
+            worker_program: None,
+            worker_args: Vec::new(),
+            request_timeout: Duration::from_secs(300),
+            load_timeout: Duration::from_secs(120),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum WorkerError {
+    /// The worker process exited or closed its pipes mid-request.
+    WorkerCrashed { status: Option<ExitStatus> },
+    /// The worker did not answer in time and was killed.
+    TimedOut { timeout: Duration },
+    /// The worker answered with a frame that could not be decoded.
+    Protocol(String),
+}
+
+impl fmt::Display for WorkerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::WorkerCrashed {
+                status: Some(status),
+            } => write!(f, "engine worker crashed ({status})"),
+            Self::WorkerCrashed { status: None } => write!(f, "engine worker crashed"),
+            Self::TimedOut { timeout } => write!(
+                f,
+                "engine worker did not respond within {:.1}s and was killed",
+                timeout.as_secs_f32()
+            ),
+            Self::Protocol(message) => write!(f, "engine worker protocol error: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for WorkerError {}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum WorkerRequest<M, I> {
+    LoadModel {
+        model_path: PathBuf,
+        params: M,
+    },
+    UnloadModel,
+    TranscribeSamples {
+        samples: Vec<f32>,
+        params: Option<I>,
+    },
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum WorkerResponse {
+    Loaded,
+    Unloaded,
+    Transcribed { result: TranscriptionResult },
+    Error { message: String },
+}
+
+/// Runs `E` in a child process so a crash in native code cannot take down the host.
+///
+/// The worker binary must call [`run_worker_if_requested`] with the same engine type
+/// at the top of `main`.
+pub struct IsolatedEngine<E: TranscriptionEngine> {
+    options: IsolatedEngineOptions,
+    worker: Option<Worker>,
+    loaded: Option<(PathBuf, E::ModelParams)>,
+    _engine: PhantomData<fn() -> E>,
+}
+
+impl<E: TranscriptionEngine> Default for IsolatedEngine<E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<E: TranscriptionEngine> IsolatedEngine<E> {
+    pub fn new() -> Self {
+        Self::with_options(IsolatedEngineOptions::default())
+    }
+
+    pub fn with_options(options: IsolatedEngineOptions) -> Self {
+        Self {
+            options,
+            worker: None,
+            loaded: None,
+            _engine: PhantomData,
+        }
+    }
+
+    /// Process id of the running worker, if one is alive.
+    pub fn worker_id(&self) -> Option<u32> {
+        self.worker.as_ref().map(|worker| worker.child.id())
+    }
+}
+
+impl<E> IsolatedEngine<E>
+where
+    E: TranscriptionEngine,
+    E::ModelParams: Serialize + Clone,
+    E::InferenceParams: Serialize,
+{
+    fn ensure_worker(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        if self.worker.is_some() {
+            return Ok(());
+        }
+
+        self.worker = Some(Worker::spawn(&self.options)?);
+
+        // A restarted worker starts empty; replay the last successful load.
+        if let Some((model_path, params)) = self.loaded.clone() {
+            let request = WorkerRequest::<_, E::InferenceParams>::LoadModel { model_path, params };
+            match self.round_trip(&request, self.options.load_timeout)? {
+                WorkerResponse::Loaded => {}
+                other => return Err(unexpected_response(other)),
+            }
+        }
+
+        Ok(())
+    }
+
+    fn request(
+        &mut self,
+        request: &WorkerRequest<E::ModelParams, E::InferenceParams>,
+        timeout: Duration,
+    ) -> Result<WorkerResponse, Box<dyn std::error::Error>> {
+        self.ensure_worker()?;
+        self.round_trip(request, timeout)
+    }
+
+    fn round_trip<M: Serialize, I: Serialize>(
+        &mut self,
+        request: &WorkerRequest<M, I>,
+        timeout: Duration,
+    ) -> Result<WorkerResponse, Box<dyn std::error::Error>> {
+        let worker = self
+            .worker
+            .as_mut()
+            .ok_or_else(|| io_error("engine worker is not running"))?;
+
+        let outcome = worker.round_trip(request, timeout);
+        if let Err(error) = &outcome {
+            if error.downcast_ref::<WorkerError>().is_some() {
+                // The worker is dead or wedged; the next request starts a fresh one.
+                if let Some(mut worker) = self.worker.take() {
+                    worker.kill();
+                }
+            }
+        }
+
+        match outcome? {
+            WorkerResponse::Error { message } => Err(io_error(message)),
+            response => Ok(response),
+        }
+    }
+}
+
+impl<E: TranscriptionEngine> Drop for IsolatedEngine<E> {
+    fn drop(&mut self) {
+        if let Some(mut worker) = self.worker.take() {
+            worker.kill();
+        }
+    }
+}
+
+impl<E> TranscriptionEngine for IsolatedEngine<E>
+where
+    E: TranscriptionEngine,
+    E::ModelParams: Serialize + Clone,
+    E::InferenceParams: Serialize,
+{
+    type InferenceParams = E::InferenceParams;
+    type ModelParams = E::ModelParams;
+
+    fn load_model_with_params(
+        &mut self,
+        model_path: &Path,
+        params: Self::ModelParams,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        self.loaded = None;
+
+        let request = WorkerRequest::LoadModel {
+            model_path: model_path.to_path_buf(),
+            params: params.clone(),
+        };
+        match self.request(&request, self.options.load_timeout)? {
+            WorkerResponse::Loaded => {
+                self.loaded = Some((model_path.to_path_buf(), params));
+                Ok(())
+            }
+            other => Err(unexpected_response(other)),
+        }
+    }
+
+    fn unload_model(&mut self) {
+        self.loaded = None;
+        if self.worker.is_some() {
+            let _ = self.round_trip(
+                &WorkerRequest::<E::ModelParams, E::InferenceParams>::UnloadModel,
+                self.options.request_timeout,
+            );
+        }
+    }
+
+    fn transcribe_samples(
+        &mut self,
+        samples: Vec<f32>,
+        params: Option<Self::InferenceParams>,
+    ) -> Result<TranscriptionResult, Box<dyn std::error::Error>> {
+        if self.loaded.is_none() {
+            return Err(io_error("Model not loaded. Call load_model() first."));
+        }
+
+        let request = WorkerRequest::TranscribeSamples { samples, params };
+        match self.request(&request, self.options.request_timeout)? {
+            WorkerResponse::Transcribed { result } => Ok(result),
+            other => Err(unexpected_response(other)),
+        }
+    }
+}
+
+struct Worker {
+    child: Child,
+    stdin: BufWriter<ChildStdin>,
+    responses: Receiver<std::io::Result<Vec<u8>>>,
+}
+
+impl Worker {
+    fn spawn(options: &IsolatedEngineOptions) -> Result<Self, Box<dyn std::error::Error>> {
+        let program = match &options.worker_program {
+            Some(program) => program.clone(),
+            None => std::env::current_exe()?,
+        };
+
+        let mut child = Command::new(&program)
+            .args(&options.worker_args)
+            .env(WORKER_ENV_VAR, "1")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit())
+            .spawn()
+            .map_err(|error| {
+                io_error(format!(
+                    "failed to start engine worker {}: {error}",
+                    program.display()
+                ))
+            })?;
+
+        let stdin = child
+            .stdin
+            .take()
+            .ok_or_else(|| io_error("engine worker stdin is unavailable"))?;
+        let stdout = child
+            .stdout
+            .take()
+            .ok_or_else(|| io_error("engine worker stdout is unavailable"))?;
+
+        let (sender, responses) = mpsc::channel();
+        std::thread::spawn(move || {
+            let mut reader = BufReader::new(stdout);
+            loop {
+                let frame = match read_frame(&mut reader) {
+                    Ok(Some(frame)) => Ok(frame),
+                    Ok(None) => break,
+                    Err(error) => Err(error),
+                };
+                let failed = frame.is_err();
+                if sender.send(frame).is_err() || failed {
+                    break;
+                }
+            }
+        });
+
+        Ok(Self {
+            child,
+            stdin: BufWriter::new(stdin),
+            responses,
+        })
+    }
+
+    fn round_trip<M: Serialize, I: Serialize>(
+        &mut self,
+        request: &WorkerRequest<M, I>,
+        timeout: Duration,
+    ) -> Result<WorkerResponse, Box<dyn std::error::Error>> {
+        let payload = serde_json::to_vec(request)?;
+        if write_frame(&mut self.stdin, &payload).is_err() {
+            return Err(self.crashed());
+        }
+
+        match self.responses.recv_timeout(timeout) {
+            Ok(Ok(frame)) => serde_json::from_slice(&frame)
+                .map_err(|error| WorkerError::Protocol(error.to_string()).into()),
+            Ok(Err(_)) | Err(RecvTimeoutError::Disconnected) => Err(self.crashed()),
+            Err(RecvTimeoutError::Timeout) => {
+                self.kill();
+                Err(WorkerError::TimedOut { timeout }.into())
+            }
+        }
+    }
+
+    fn crashed(&mut self) -> Box<dyn std::error::Error> {
+        // The pipes are gone, so the process is exiting (or already has); reap it.
+        let status = match self.child.try_wait() {
+            Ok(Some(status)) => Some(status),
+            _ => {
+                let _ = self.child.kill();
+                self.child.wait().ok()
+            }
+        };
+        WorkerError::WorkerCrashed { status }.into()
+    }
+
+    fn kill(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// Turns the current process into an engine worker when spawned by [`IsolatedEngine`].
+///
+/// Returns immediately in a normal process. In a worker it serves requests from
+/// stdin until the parent closes the pipe, then exits. Nothing else may write to
+/// stdout while the worker runs.
+pub fn run_worker_if_requested<E>()
+where
+    E: TranscriptionEngine + Default,
+    E::ModelParams: DeserializeOwned,
+    E::InferenceParams: DeserializeOwned,
+{
+    if std::env::var_os(WORKER_ENV_VAR).is_none() {
+        return;
+    }
+
+    let stdin = std::io::stdin();
+    let stdout = std::io::stdout();
+    let code = match run_worker::<E>(stdin.lock(), stdout.lock()) {
+        Ok(()) => 0,
+        Err(error) => {
+            eprintln!("[glimpse-speech] engine worker failed: {error}");
+            1
+        }
+    };
+    std::process::exit(code);
+}
+
+/// Serves worker requests from `input` until EOF.
+pub fn run_worker<E>(input: impl Read, output: impl Write) -> std::io::Result<()>
+where
+    E: TranscriptionEngine + Default,
+    E::ModelParams: DeserializeOwned,
+    E::InferenceParams: DeserializeOwned,
+{
+    let mut input = BufReader::new(input);
+    let mut output = BufWriter::new(output);
+    let mut engine = E::default();
+
+    while let Some(frame) = read_frame(&mut input)? {
+        let response = match serde_json::from_slice::<
+            WorkerRequest<E::ModelParams, E::InferenceParams>,
+        >(&frame)
+        {
+            Ok(request) => handle_request(&mut engine, request),
+            Err(error) => WorkerResponse::Error {
+                message: format!("invalid worker request: {error}"),
+            },
+        };
+
+        let payload = serde_json::to_vec(&response).map_err(std::io::Error::other)?;
+        write_frame(&mut output, &payload)?;
+    }
+
+    Ok(())
+}
+
+fn handle_request<E: TranscriptionEngine>(
+    engine: &mut E,
+    request: WorkerRequest<E::ModelParams, E::InferenceParams>,
+) -> WorkerResponse {
+    let outcome = match request {
+        WorkerRequest::LoadModel { model_path, params } => engine
+            .load_model_with_params(&model_path, params)
+            .map(|()| WorkerResponse::Loaded),
+        WorkerRequest::UnloadModel => {
+            engine.unload_model();
+            Ok(WorkerResponse::Unloaded)
+        }
+        WorkerRequest::TranscribeSamples { samples, params } => engine
+            .transcribe_samples(samples, params)
+            .map(|result| WorkerResponse::Transcribed { result }),
+    };
+
+    outcome.unwrap_or_else(|error| WorkerResponse::Error {
+        message: error.to_string(),
+    })
+}
+
+fn write_frame(writer: &mut impl Write, payload: &[u8]) -> std::io::Result<()> {
+    let len = u32::try_from(payload.len())
+        .map_err(|_| std::io::Error::other("worker frame is too large"))?;
+    writer.write_all(&len.to_le_bytes())?;
+    writer.write_all(payload)?;
+    writer.flush()
+}
+
+/// `Ok(None)` only for a stream that ends cleanly between frames; a stream that ends
+/// inside a frame is an `InvalidData` error.
+fn read_frame(reader: &mut impl Read) -> std::io::Result<Option<Vec<u8>>> {
+    let mut len_bytes = [0u8; 4];
+    match reader.read_exact(&mut len_bytes[..1]) {
+        Ok(()) => {}
+        Err(error) if error.kind() == std::io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(error) => return Err(error),
+    }
+    reader
+        .read_exact(&mut len_bytes[1..])
+        .map_err(|error| truncated_frame(error, "length prefix"))?;
+
+    let len = u32::from_le_bytes(len_bytes) as usize;
+    if len > MAX_FRAME_BYTES {
+        return Err(std::io::Error::other(format!(
+            "worker frame of {len} bytes exceeds the {MAX_FRAME_BYTES} byte limit"
+        )));
+    }
+
+    let mut frame = vec![0u8; len];
+    reader
+        .read_exact(&mut frame)
+        .map_err(|error| truncated_frame(error, "body"))?;
+    Ok(Some(frame))
+}
+
+fn truncated_frame(error: std::io::Error, part: &str) -> std::io::Error {
+    if error.kind() == std::io::ErrorKind::UnexpectedEof {
+        std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!("worker stream ended inside a frame {part}"),
+        )
+    } else {
+        error
+    }
+}
+
+fn unexpected_response(response: WorkerResponse) -> Box<dyn std::error::Error> {
+    WorkerError::Protocol(format!("unexpected worker response: {response:?}")).into()
+}
+
+fn io_error(message: impl Into<String>) -> Box<dyn std::error::Error> {
+    std::io::Error::other(message.into()).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{read_frame, write_frame};
+
+    #[test]
+    fn frames_round_trip_with_length_prefix() {
+        let mut buffer = Vec::new();
+        write_frame(&mut buffer, br#"{"type":"unload_model"}"#).expect("frame should encode");
+        write_frame(&mut buffer, b"").expect("empty frame should encode");
+
+        assert_eq!(&buffer[..4], &23u32.to_le_bytes());
+
+        let mut reader = buffer.as_slice();
+        assert_eq!(
+            read_frame(&mut reader).expect("first frame"),
+            Some(br#"{"type":"unload_model"}"#.to_vec())
+        );
+        assert_eq!(
+            read_frame(&mut reader).expect("second frame"),
+            Some(Vec::new())
+        );
+        assert_eq!(read_frame(&mut reader).expect("eof"), None);
+    }
+
+    #[test]
+    fn truncated_frames_are_errors_not_end_of_stream() {
+        let mut buffer = Vec::new();
+        write_frame(&mut buffer, b"payload").expect("frame should encode");
+
+        for cut in [1, 3, 6] {
+            let error = read_frame(&mut &buffer[..cut])
+                .expect_err("a partial frame must not read as a clean end");
+            assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+        }
+    }
+}
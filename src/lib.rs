@@ -1,21 +1,113 @@
 pub mod audio;
+pub mod batch;
 pub mod engines;
+pub mod format;
+#[cfg(any(feature = "whisperfile", feature = "remote"))]
+pub(crate) mod http;
+#[cfg(feature = "isolated")]
+pub mod isolated;
+pub mod pool;
+#[cfg(feature = "server")]
+pub mod server;
+pub mod vocabulary;
 
+use std::fmt;
 use std::path::Path;
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TranscriptionResult {
     pub text: String,
     pub segments: Option<Vec<TranscriptionSegment>>,
+    /// Word-level timings, when the engine produces them.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub words: Option<Vec<TranscriptionWord>>,
+    /// Sub-word token timings, when the engine produces them.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub tokens: Option<Vec<TranscriptionToken>>,
+    /// What degraded this result without failing it; empty for a clean result.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub warnings: Vec<Warning>,
 }
 
+/// A non-fatal problem with a transcription.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Warning {
+    /// The vocabulary terms were not used while decoding, with the reason.
+    VocabularyBoostingDisabled(String),
+    /// This many segments had no duration and were left out.
+    SegmentsDropped(usize),
+    /// The engine does not use the requested language, e.g. because it detects the
+    /// language itself.
+    LanguageHintIgnored(String),
+    /// A warning this crate has no kind for, e.g. from a newer Fluid bridge.
+    Other(String),
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Warning::VocabularyBoostingDisabled(reason) => {
+                write!(f, "vocabulary boosting disabled: {reason}")
+            }
+            Warning::SegmentsDropped(count) => {
+                write!(f, "dropped {count} segments without duration")
+            }
+            Warning::LanguageHintIgnored(language) => {
+                write!(f, "language hint {language:?} was ignored")
+            }
+            Warning::Other(message) => f.write_str(message),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TranscriptionSegment {
     /// Segment start time in seconds.
     pub start: f32,
     /// Segment end time in seconds.
     pub end: f32,
     pub text: String,
+    /// Speaker index from engines that detect speaker turns (Whisper with tinydiarize).
+    /// Starts at 0 and goes up by one at every detected turn, so it separates turns
+    /// but does not identify who is speaking.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub speaker: Option<u32>,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TranscriptionWord {
+    /// Word start time in seconds.
+    pub start: f32,
+    /// Word end time in seconds.
+    pub end: f32,
+    pub text: String,
+}
+
+/// A sub-word piece as the model emitted it. `text` starts with a space when the piece
+/// begins a word, so joining the tokens rebuilds the transcript.
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TranscriptionToken {
+    /// Token start time in seconds.
+    pub start: f32,
+    /// Token end time in seconds.
+    pub end: f32,
+    pub text: String,
+}
+
+/// A span of audio attributed to one speaker by diarization.
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SpeakerTurn {
+    /// Turn start time in seconds.
+    pub start: f32,
+    /// Turn end time in seconds.
+    pub end: f32,
+    pub speaker: String,
 }
 
 pub trait TranscriptionEngine {
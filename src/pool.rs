@@ -0,0 +1,214 @@
+use std::fmt;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::mpsc::{self, Receiver, SyncSender, TrySendError};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+
+use crate::{TranscriptionEngine, TranscriptionResult};
+
+type Task<E> = Box<dyn FnOnce(&mut E) + Send>;
+
+#[derive(Debug, Clone)]
+pub struct PoolOptions {
+    /// Engine instances, i.e. tasks running at the same time.
+    pub workers: usize,
+    /// Tasks allowed to wait for a free engine.
+    pub queue_capacity: usize,
+}
+
+impl Default for PoolOptions {
+    fn default() -> Self {
+        Self {
+            workers: 1,
+            queue_capacity: 64,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PoolError {
+    /// `try_execute` found every worker busy and the queue full.
+    QueueFull,
+    /// The pool has no running workers left.
+    Closed,
+}
+
+impl fmt::Display for PoolError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PoolError::QueueFull => f.write_str("engine pool queue is full"),
+            PoolError::Closed => f.write_str("engine pool is closed"),
+        }
+    }
+}
+
+impl std::error::Error for PoolError {}
+
+/// A fixed set of engines, each owned by its own worker thread, fed from a bounded queue.
+///
+/// Engines are built on their worker thread by `factory`, so `E` does not need to be `Send`.
+pub struct EnginePool<E> {
+    tasks: Option<SyncSender<Task<E>>>,
+    workers: Vec<JoinHandle<()>>,
+}
+
+impl<E: 'static> EnginePool<E> {
+    /// Starts `options.workers` engines and waits until all of them are ready.
+    pub fn new<F>(options: PoolOptions, factory: F) -> Result<Self, Box<dyn std::error::Error>>
+    where
+        F: Fn() -> Result<E, Box<dyn std::error::Error>> + Send + Sync + 'static,
+    {
+        if options.workers == 0 {
+            return Err(io_error("engine pool needs at least one worker"));
+        }
+
+        let (tasks, queue) = mpsc::sync_channel::<Task<E>>(options.queue_capacity);
+        let queue = Arc::new(Mutex::new(queue));
+        let factory = Arc::new(factory);
+        let (ready_sender, ready) = mpsc::channel();
+        let mut workers = Vec::with_capacity(options.workers);
+
+        for index in 0..options.workers {
+            let queue = Arc::clone(&queue);
+            let factory = Arc::clone(&factory);
+            let ready_sender = ready_sender.clone();
+            let worker = thread::Builder::new()
+                .name(format!("glimpse-engine-{index}"))
+                .spawn(move || match factory() {
+                    Ok(engine) => {
+                        let _ = ready_sender.send(Ok(()));
+                        drop(ready_sender);
+                        run_worker(engine, &queue);
+                    }
+                    Err(error) => {
+                        let _ = ready_sender.send(Err(error.to_string()));
+                    }
+                })?;
+            workers.push(worker);
+        }
+        drop(ready_sender);
+
+        let mut pool = Self {
+            tasks: Some(tasks),
+            workers,
+        };
+        let mut started = 0;
+        for status in ready.iter().take(options.workers) {
+            if let Err(message) = status {
+                pool.shutdown();
+                return Err(io_error(format!("failed to start engine: {message}")));
+            }
+            started += 1;
+        }
+        // A panicking factory drops its sender without reporting.
+        if started < options.workers {
+            pool.shutdown();
+            return Err(io_error("engine worker panicked during startup"));
+        }
+        Ok(pool)
+    }
+
+    pub fn workers(&self) -> usize {
+        self.workers.len()
+    }
+
+    /// Queues `task` for the next free engine, blocking while the queue is full.
+    /// The receiver yields the task's return value, or disconnects if the task panicked.
+    pub fn execute<R, T>(&self, task: T) -> Result<Receiver<R>, PoolError>
+    where
+        T: FnOnce(&mut E) -> R + Send + 'static,
+        R: Send + 'static,
+    {
+        let (task, reply) = wrap_task(task);
+        self.sender()?.send(task).map_err(|_| PoolError::Closed)?;
+        Ok(reply)
+    }
+
+    /// Like [`EnginePool::execute`], but fails with [`PoolError::QueueFull`] instead of waiting.
+    pub fn try_execute<R, T>(&self, task: T) -> Result<Receiver<R>, PoolError>
+    where
+        T: FnOnce(&mut E) -> R + Send + 'static,
+        R: Send + 'static,
+    {
+        let (task, reply) = wrap_task(task);
+        self.sender()?.try_send(task).map_err(|error| match error {
+            TrySendError::Full(_) => PoolError::QueueFull,
+            TrySendError::Disconnected(_) => PoolError::Closed,
+        })?;
+        Ok(reply)
+    }
+
+    fn sender(&self) -> Result<&SyncSender<Task<E>>, PoolError> {
+        self.tasks.as_ref().ok_or(PoolError::Closed)
+    }
+}
+
+impl<E> EnginePool<E> {
+    /// Lets queued tasks finish, then stops the workers.
+    fn shutdown(&mut self) {
+        self.tasks = None;
+        for worker in self.workers.drain(..) {
+            let _ = worker.join();
+        }
+    }
+}
+
+impl<E> EnginePool<E>
+where
+    E: TranscriptionEngine + 'static,
+    E::InferenceParams: Send,
+{
+    /// Transcribes on the next free engine and waits for the result.
+    pub fn transcribe_samples(
+        &self,
+        samples: Vec<f32>,
+        params: Option<E::InferenceParams>,
+    ) -> Result<TranscriptionResult, Box<dyn std::error::Error>> {
+        self.execute(move |engine| {
+            engine
+                .transcribe_samples(samples, params)
+                .map_err(|error| error.to_string())
+        })?
+        .recv()
+        .map_err(|_| io_error("engine panicked during transcription"))?
+        .map_err(io_error)
+    }
+}
+
+impl<E> Drop for EnginePool<E> {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+fn wrap_task<E, R, T>(task: T) -> (Task<E>, Receiver<R>)
+where
+    T: FnOnce(&mut E) -> R + Send + 'static,
+    R: Send + 'static,
+{
+    let (reply_sender, reply) = mpsc::channel();
+    let task: Task<E> = Box::new(move |engine: &mut E| {
+        let _ = reply_sender.send(task(engine));
+    });
+    (task, reply)
+}
+
+fn run_worker<E>(mut engine: E, queue: &Mutex<Receiver<Task<E>>>) {
+    loop {
+        // The lock is only held while waiting, never while a task runs.
+        let task = match queue.lock() {
+            Ok(queue) => queue.recv(),
+            Err(_) => return,
+        };
+        let Ok(task) = task else {
+            return;
+        };
+
+        // A panicking task drops its reply sender; the engine keeps serving.
+        let _ = catch_unwind(AssertUnwindSafe(|| task(&mut engine)));
+    }
+}
+
+fn io_error(message: impl Into<String>) -> Box<dyn std::error::Error> {
+    std::io::Error::other(message.into()).into()
+}
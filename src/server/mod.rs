@@ -0,0 +1,522 @@
+//! OpenAI-compatible `/v1/audio/transcriptions` and `/v1/audio/translations` server.
+
+mod multipart;
+
+use std::io::Read;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+
+use serde_json::json;
+use tiny_http::{Header, Method, Request, Response};
+
+use crate::engines::any::{AnyEngine, AnyInferenceParams};
+use crate::format::{render_srt, render_vtt, ResponseFormat};
+use crate::pool::{EnginePool, PoolError, PoolOptions};
+use crate::{TranscriptionResult, TranscriptionSegment};
+
+const SAMPLE_RATE: f32 = 16_000.0;
+/// The only upload format the server decodes; anything else is answered with HTTP 415.
+pub const ACCEPTED_AUDIO_FORMAT: &str = "16 kHz mono 16-bit PCM WAV";
+
+#[derive(Debug, Clone)]
+pub struct ServerConfig {
+    pub host: String,
+    /// `0` picks a free port; see [`TranscriptionServer::local_addr`].
+    pub port: u16,
+    /// Number of engine instances, i.e. requests transcribed at the same time.
+    pub max_concurrency: usize,
+    /// Requests allowed to wait for a free engine; further requests get HTTP 503.
+    pub queue_capacity: usize,
+    pub max_upload_bytes: usize,
+    /// Model id reported by `GET /v1/models`.
+    pub model_name: String,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            host: "127.0.0.1".to_string(),
+            port: 8000,
+            max_concurrency: 1,
+            queue_capacity: 16,
+            max_upload_bytes: 100 * 1024 * 1024,
+            model_name: "glimpse".to_string(),
+        }
+    }
+}
+
+pub struct TranscriptionServer {
+    http: Arc<tiny_http::Server>,
+    engines: Arc<EnginePool<Box<dyn AnyEngine>>>,
+    admission: Arc<Admission>,
+    config: ServerConfig,
+}
+
+impl TranscriptionServer {
+    /// Binds the listener and starts an [`EnginePool`] of `max_concurrency` engines, each
+    /// built by `factory` on its own thread. Fails if any engine fails to load.
+    pub fn start<F>(config: ServerConfig, factory: F) -> Result<Self, Box<dyn std::error::Error>>
+    where
+        F: Fn() -> Result<Box<dyn AnyEngine>, Box<dyn std::error::Error>> + Send + Sync + 'static,
+    {
+        if config.max_concurrency == 0 {
+            return Err(io_error("max_concurrency must be at least 1"));
+        }
+
+        let engines = EnginePool::new(
+            PoolOptions {
+                workers: config.max_concurrency,
+                queue_capacity: config.queue_capacity,
+            },
+            factory,
+        )?;
+
+        let address = listen_address(&config.host, config.port);
+        let http = tiny_http::Server::http(address.as_str())
+            .map_err(|error| io_error(format!("failed to bind {address}: {error}")))?;
+
+        Ok(Self {
+            http: Arc::new(http),
+            engines: Arc::new(engines),
+            admission: Arc::new(Admission::new(
+                config.max_concurrency + config.queue_capacity,
+            )),
+            config,
+        })
+    }
+
+    pub fn local_addr(&self) -> Option<SocketAddr> {
+        self.http.server_addr().to_ip()
+    }
+
+    /// Serves requests until [`TranscriptionServer::shutdown`] is called.
+    ///
+    /// At most `max_concurrency + queue_capacity` transcription requests are handled at once,
+    /// each on its own thread. Further uploads get HTTP 503 before their body is read; other
+    /// routes are answered on the accepting thread.
+    pub fn run(&self) {
+        for request in self.http.incoming_requests() {
+            if !is_transcription_route(&request) {
+                handle_request(request, &self.engines, &self.config);
+                continue;
+            }
+            let Some(slot) = self.admission.try_acquire() else {
+                respond(request, Err(at_capacity()));
+                continue;
+            };
+            let engines = Arc::clone(&self.engines);
+            let config = self.config.clone();
+            thread::spawn(move || {
+                handle_request(request, &engines, &config);
+                drop(slot);
+            });
+        }
+    }
+
+    /// Unblocks [`TranscriptionServer::run`]. Workers exit once the server is dropped.
+    pub fn shutdown(&self) {
+        self.http.unblock();
+    }
+}
+
+/// Counts transcription requests in flight, from accepting the upload to sending the response.
+struct Admission {
+    in_flight: AtomicUsize,
+    capacity: usize,
+}
+
+impl Admission {
+    fn new(capacity: usize) -> Self {
+        Self {
+            in_flight: AtomicUsize::new(0),
+            capacity,
+        }
+    }
+
+    fn try_acquire(self: &Arc<Self>) -> Option<AdmissionSlot> {
+        self.in_flight
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |count| {
+                (count < self.capacity).then_some(count + 1)
+            })
+            .ok()?;
+        Some(AdmissionSlot(Arc::clone(self)))
+    }
+}
+
+struct AdmissionSlot(Arc<Admission>);
+
+impl Drop for AdmissionSlot {
+    fn drop(&mut self) {
+        self.0.in_flight.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+fn is_transcription_route(request: &Request) -> bool {
+    let path = request.url().split('?').next().unwrap_or_default();
+    *request.method() == Method::Post
+        && matches!(path, "/v1/audio/transcriptions" | "/v1/audio/translations")
+}
+
+fn at_capacity() -> HttpError {
+    HttpError::new(503, "server is at capacity, retry later")
+}
+
+fn listen_address(host: &str, port: u16) -> String {
+    if host.contains(':') {
+        format!("[{host}]:{port}")
+    } else {
+        format!("{host}:{port}")
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Task {
+    Transcribe,
+    Translate,
+}
+
+impl Task {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Task::Transcribe => "transcribe",
+            Task::Translate => "translate",
+        }
+    }
+}
+
+struct HttpError {
+    status: u16,
+    message: String,
+}
+
+impl HttpError {
+    fn new(status: u16, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            message: message.into(),
+        }
+    }
+
+    fn bad_request(message: impl Into<String>) -> Self {
+        Self::new(400, message)
+    }
+}
+
+fn handle_request(
+    mut request: Request,
+    engines: &EnginePool<Box<dyn AnyEngine>>,
+    config: &ServerConfig,
+) {
+    let path = request
+        .url()
+        .split('?')
+        .next()
+        .unwrap_or_default()
+        .to_string();
+    let method = request.method().clone();
+
+    let response = match (method, path.as_str()) {
+        (Method::Get, "/health") => Ok(json_response(200, &json!({ "status": "ok" }))),
+        (Method::Get, "/v1/models") => Ok(json_response(
+            200,
+            &json!({
+                "object": "list",
+                "data": [{ "id": config.model_name, "object": "model", "owned_by": "glimpse" }],
+            }),
+        )),
+        (Method::Post, "/v1/audio/transcriptions") => {
+            transcribe(&mut request, Task::Transcribe, engines, config)
+        }
+        (Method::Post, "/v1/audio/translations") => {
+            transcribe(&mut request, Task::Translate, engines, config)
+        }
+        _ => Err(HttpError::new(404, format!("no route for {path}"))),
+    };
+    respond(request, response);
+}
+
+fn respond(request: Request, response: Result<Response<std::io::Cursor<Vec<u8>>>, HttpError>) {
+    let response = response.unwrap_or_else(|error| {
+        let kind = match error.status {
+            400..=499 => "invalid_request_error",
+            _ => "server_error",
+        };
+        let mut response = json_response(
+            error.status,
+            &json!({ "error": { "message": error.message, "type": kind } }),
+        );
+        if error.status == 503 {
+            response.add_header(header("Retry-After", "1"));
+        }
+        response
+    });
+    let _ = request.respond(response);
+}
+
+struct TranscriptionRequest {
+    audio: Vec<u8>,
+    params: AnyInferenceParams,
+    response_format: ResponseFormat,
+    segment_timestamps: bool,
+}
+
+fn transcribe(
+    request: &mut Request,
+    task: Task,
+    engines: &EnginePool<Box<dyn AnyEngine>>,
+    config: &ServerConfig,
+) -> Result<Response<std::io::Cursor<Vec<u8>>>, HttpError> {
+    let parsed = parse_transcription_request(request, task, config)?;
+    let samples = crate::audio::decode_wav_bytes(&parsed.audio).map_err(|error| {
+        HttpError::new(
+            415,
+            format!(
+                "unsupported audio file ({error}); only {ACCEPTED_AUDIO_FORMAT} is accepted, \
+                 convert with `ffmpeg -i <input> -ar 16000 -ac 1 -c:a pcm_s16le <output>.wav`"
+            ),
+        )
+    })?;
+    let duration = samples.len() as f32 / SAMPLE_RATE;
+    let language = parsed.params.language.clone();
+    let word_timestamps = parsed.params.word_timestamps;
+
+    let params = parsed.params;
+    let result = engines
+        .try_execute(move |engine| {
+            engine
+                .transcribe_samples(samples, &params)
+                .map_err(|error| error.to_string())
+        })
+        .map_err(|error| match error {
+            PoolError::QueueFull => at_capacity(),
+            PoolError::Closed => HttpError::new(500, "no transcription workers running"),
+        })?
+        .recv()
+        .map_err(|_| HttpError::new(500, "transcription worker stopped"))?
+        .map_err(|message| HttpError::new(500, message))?;
+
+    Ok(render_response(
+        result,
+        parsed.response_format,
+        task,
+        language.as_deref(),
+        duration,
+        parsed.segment_timestamps,
+        word_timestamps,
+    ))
+}
+
+fn parse_transcription_request(
+    request: &mut Request,
+    task: Task,
+    config: &ServerConfig,
+) -> Result<TranscriptionRequest, HttpError> {
+    let content_type = request
+        .headers()
+        .iter()
+        .find(|header| header.field.equiv("Content-Type"))
+        .map(|header| header.value.as_str().to_string())
+        .unwrap_or_default();
+    let boundary = multipart::boundary(&content_type)
+        .ok_or_else(|| HttpError::bad_request("expected a multipart/form-data body"))?;
+
+    if request.body_length().unwrap_or(0) > config.max_upload_bytes {
+        return Err(HttpError::new(413, "upload is too large"));
+    }
+    let mut body = Vec::new();
+    request
+        .as_reader()
+        .take(config.max_upload_bytes as u64 + 1)
+        .read_to_end(&mut body)
+        .map_err(|error| HttpError::bad_request(format!("failed to read body: {error}")))?;
+    if body.len() > config.max_upload_bytes {
+        return Err(HttpError::new(413, "upload is too large"));
+    }
+
+    let parts = multipart::parse(&body, &boundary)
+        .map_err(|error| HttpError::bad_request(error.to_string()))?;
+
+    let mut parsed = TranscriptionRequest {
+        audio: Vec::new(),
+        params: AnyInferenceParams {
+            translate: task == Task::Translate,
+            ..Default::default()
+        },
+        response_format: ResponseFormat::Json,
+        segment_timestamps: true,
+    };
+    let mut has_file = false;
+    let mut granularities = Vec::new();
+
+    for part in parts {
+        let text = || {
+            part.text()
+                .map_err(|error| HttpError::bad_request(error.to_string()))
+        };
+        match part.name.as_str() {
+            "file" => {
+                has_file = true;
+                parsed.audio = part.data.clone();
+            }
+            // Only one model is served; the requested name is accepted as-is.
+            "model" => {}
+            "language" if task == Task::Transcribe => {
+                let language = text()?;
+                if !language.is_empty() {
+                    parsed.params.language = Some(language.to_string());
+                }
+            }
+            "prompt" => {
+                let prompt = text()?;
+                if !prompt.is_empty() {
+                    parsed.params.prompt = Some(prompt.to_string());
+                }
+            }
+            "vocabulary" | "vocabulary[]" => {
+                let terms = crate::vocabulary::Vocabulary::parse(text()?);
+                parsed
+                    .params
+                    .vocabulary
+                    .extend(terms.terms().iter().cloned());
+            }
+            "correct_vocabulary" => {
+                parsed.params.correct_vocabulary = match text()? {
+                    "true" => true,
+                    "false" => false,
+                    other => {
+                        return Err(HttpError::bad_request(format!(
+                            "correct_vocabulary must be 'true' or 'false', got '{other}'"
+                        )))
+                    }
+                };
+            }
+            "response_format" => {
+                parsed.response_format = text()?.parse().map_err(
+                    |error: crate::format::UnsupportedResponseFormat| {
+                        HttpError::bad_request(error.to_string())
+                    },
+                )?;
+            }
+            "temperature" => {
+                let value = text()?;
+                let temperature = value.parse::<f32>().map_err(|_| {
+                    HttpError::bad_request(format!("temperature must be a number, got '{value}'"))
+                })?;
+                if !(0.0..=1.0).contains(&temperature) {
+                    return Err(HttpError::bad_request(
+                        "temperature must be between 0.0 and 1.0",
+                    ));
+                }
+                parsed.params.temperature = Some(temperature);
+            }
+            "timestamp_granularities[]" | "timestamp_granularities" => match text()? {
+                value @ ("word" | "segment") => granularities.push(value.to_string()),
+                other => {
+                    return Err(HttpError::bad_request(format!(
+                        "timestamp_granularities must be 'word' or 'segment', got '{other}'"
+                    )))
+                }
+            },
+            _ => {}
+        }
+    }
+
+    if !has_file {
+        return Err(HttpError::bad_request("missing 'file' form field"));
+    }
+    if !granularities.is_empty() {
+        parsed.params.word_timestamps = granularities.iter().any(|value| value == "word");
+        parsed.segment_timestamps = granularities.iter().any(|value| value == "segment");
+    }
+
+    Ok(parsed)
+}
+
+fn render_response(
+    result: TranscriptionResult,
+    format: ResponseFormat,
+    task: Task,
+    language: Option<&str>,
+    duration: f32,
+    segment_timestamps: bool,
+    word_timestamps: bool,
+) -> Response<std::io::Cursor<Vec<u8>>> {
+    match format {
+        ResponseFormat::Json => json_response(200, &json!({ "text": result.text })),
+        ResponseFormat::Text => text_response(&result.text, "text/plain; charset=utf-8"),
+        ResponseFormat::Srt => text_response(
+            &render_srt(&segments_or_whole(&result, duration)),
+            "text/plain; charset=utf-8",
+        ),
+        ResponseFormat::Vtt => text_response(
+            &render_vtt(&segments_or_whole(&result, duration)),
+            "text/vtt; charset=utf-8",
+        ),
+        ResponseFormat::VerboseJson => {
+            let language = match task {
+                Task::Translate => Some("english"),
+                Task::Transcribe => language,
+            };
+            let mut body = json!({
+                "task": task.as_str(),
+                "language": language,
+                "duration": duration,
+                "text": result.text,
+            });
+            if segment_timestamps {
+                body["segments"] = segments_or_whole(&result, duration)
+                    .iter()
+                    .enumerate()
+                    .map(|(id, segment)| {
+                        json!({
+                            "id": id,
+                            "start": segment.start,
+                            "end": segment.end,
+                            "text": segment.text,
+                        })
+                    })
+                    .collect();
+            }
+            if let Some(words) = result.words.as_ref().filter(|_| word_timestamps) {
+                body["words"] = words
+                    .iter()
+                    .map(|word| json!({ "word": word.text, "start": word.start, "end": word.end }))
+                    .collect();
+            }
+            json_response(200, &body)
+        }
+    }
+}
+
+fn segments_or_whole(result: &TranscriptionResult, duration: f32) -> Vec<TranscriptionSegment> {
+    match &result.segments {
+        Some(segments) if !segments.is_empty() => segments.clone(),
+        _ if result.text.trim().is_empty() => Vec::new(),
+        _ => vec![TranscriptionSegment {
+            start: 0.0,
+            end: duration,
+            text: result.text.clone(),
+            speaker: None,
+        }],
+    }
+}
+
+fn json_response(status: u16, body: &serde_json::Value) -> Response<std::io::Cursor<Vec<u8>>> {
+    Response::from_string(body.to_string())
+        .with_status_code(status)
+        .with_header(header("Content-Type", "application/json"))
+}
+
+fn text_response(body: &str, content_type: &str) -> Response<std::io::Cursor<Vec<u8>>> {
+    Response::from_string(body).with_header(header("Content-Type", content_type))
+}
+
+fn header(name: &str, value: &str) -> Header {
+    Header::from_bytes(name.as_bytes(), value.as_bytes()).expect("static header is valid")
+}
+
+fn io_error(message: impl Into<String>) -> Box<dyn std::error::Error> {
+    std::io::Error::other(message.into()).into()
+}
@@ -0,0 +1,160 @@
+/// One part of a `multipart/form-data` body.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Part {
+    pub(crate) name: String,
+    pub(crate) file_name: Option<String>,
+    pub(crate) data: Vec<u8>,
+}
+
+impl Part {
+    pub(crate) fn text(&self) -> Result<&str, Box<dyn std::error::Error>> {
+        std::str::from_utf8(&self.data)
+            .map(str::trim)
+            .map_err(|_| io_error(format!("form field '{}' is not valid UTF-8", self.name)))
+    }
+}
+
+/// Extracts the boundary from a `multipart/form-data; boundary=...` content type.
+pub(crate) fn boundary(content_type: &str) -> Option<String> {
+    let mut params = content_type.split(';');
+    let mime = params.next()?.trim();
+    if !mime.eq_ignore_ascii_case("multipart/form-data") {
+        return None;
+    }
+
+    params.find_map(|param| {
+        let (key, value) = param.split_once('=')?;
+        if !key.trim().eq_ignore_ascii_case("boundary") {
+            return None;
+        }
+        let value = value.trim().trim_matches('"');
+        (!value.is_empty()).then(|| value.to_string())
+    })
+}
+
+pub(crate) fn parse(body: &[u8], boundary: &str) -> Result<Vec<Part>, Box<dyn std::error::Error>> {
+    let delimiter = format!("--{boundary}").into_bytes();
+    let mut position = find(body, &delimiter, 0)
+        .ok_or_else(|| io_error("multipart body has no opening boundary"))?
+        + delimiter.len();
+    let mut parts = Vec::new();
+
+    loop {
+        if body[position..].starts_with(b"--") {
+            return Ok(parts);
+        }
+        position = skip_line_break(body, position)?;
+
+        let headers_end = find(body, b"\r\n\r\n", position)
+            .ok_or_else(|| io_error("multipart part is missing its header terminator"))?;
+        let headers = std::str::from_utf8(&body[position..headers_end])
+            .map_err(|_| io_error("multipart part headers are not valid UTF-8"))?;
+        let (name, file_name) = parse_disposition(headers)?;
+
+        let data_start = headers_end + 4;
+        let mut closing = b"\r\n".to_vec();
+        closing.extend_from_slice(&delimiter);
+        let data_end = find(body, &closing, data_start)
+            .ok_or_else(|| io_error("multipart part is missing its closing boundary"))?;
+
+        parts.push(Part {
+            name,
+            file_name,
+            data: body[data_start..data_end].to_vec(),
+        });
+        position = data_end + closing.len();
+    }
+}
+
+fn parse_disposition(
+    headers: &str,
+) -> Result<(String, Option<String>), Box<dyn std::error::Error>> {
+    let disposition = headers
+        .split("\r\n")
+        .find_map(|line| {
+            let (key, value) = line.split_once(':')?;
+            key.trim()
+                .eq_ignore_ascii_case("content-disposition")
+                .then_some(value)
+        })
+        .ok_or_else(|| io_error("multipart part has no Content-Disposition header"))?;
+
+    let mut name = None;
+    let mut file_name = None;
+    for param in disposition.split(';').skip(1) {
+        let Some((key, value)) = param.split_once('=') else {
+            continue;
+        };
+        let value = value.trim().trim_matches('"').to_string();
+        match key.trim().to_ascii_lowercase().as_str() {
+            "name" => name = Some(value),
+            "filename" => file_name = Some(value),
+            _ => {}
+        }
+    }
+
+    let name = name.ok_or_else(|| io_error("multipart part has no field name"))?;
+    Ok((name, file_name))
+}
+
+fn skip_line_break(body: &[u8], position: usize) -> Result<usize, Box<dyn std::error::Error>> {
+    if body[position..].starts_with(b"\r\n") {
+        Ok(position + 2)
+    } else {
+        Err(io_error("malformed multipart boundary line"))
+    }
+}
+
+fn find(haystack: &[u8], needle: &[u8], from: usize) -> Option<usize> {
+    if from > haystack.len() {
+        return None;
+    }
+    haystack[from..]
+        .windows(needle.len())
+        .position(|window| window == needle)
+        .map(|index| from + index)
+}
+
+fn io_error(message: impl Into<String>) -> Box<dyn std::error::Error> {
+    std::io::Error::other(message.into()).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{boundary, parse};
+
+    #[test]
+    fn extracts_boundary_from_content_type() {
+        assert_eq!(
+            boundary("multipart/form-data; boundary=\"abc 1\"").as_deref(),
+            Some("abc 1")
+        );
+        assert_eq!(
+            boundary("Multipart/Form-Data;charset=utf-8; boundary=xyz").as_deref(),
+            Some("xyz")
+        );
+        assert_eq!(boundary("application/json"), None);
+    }
+
+    #[test]
+    fn parses_fields_and_binary_file() {
+        let audio = [0u8, 13, 10, 45, 45, 255];
+        let mut body = b"--xyz\r\nContent-Disposition: form-data; name=\"model\"\r\n\r\nwhisper-1\r\n--xyz\r\nContent-Disposition: form-data; name=\"file\"; filename=\"clip.wav\"\r\nContent-Type: audio/wav\r\n\r\n".to_vec();
+        body.extend_from_slice(&audio);
+        body.extend_from_slice(b"\r\n--xyz\r\nContent-Disposition: form-data; name=\"timestamp_granularities[]\"\r\n\r\nword\r\n--xyz--\r\n");
+        let parts = parse(&body, "xyz").expect("form should parse");
+
+        assert_eq!(parts.len(), 3);
+        assert_eq!(parts[0].name, "model");
+        assert_eq!(parts[0].text().unwrap(), "whisper-1");
+        assert_eq!(parts[1].file_name.as_deref(), Some("clip.wav"));
+        assert_eq!(parts[1].data, audio);
+        assert_eq!(parts[2].name, "timestamp_granularities[]");
+    }
+
+    #[test]
+    fn rejects_truncated_body() {
+        let body = b"--b\r\nContent-Disposition: form-data; name=\"model\"\r\n\r\nwhisper";
+        assert!(parse(body, "b").is_err());
+    }
+}
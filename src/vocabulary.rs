@@ -0,0 +1,283 @@
+//! A user dictionary of names and terms that every engine can use: Fluid engines boost
+//! them during decoding, Whisper-style engines get a biasing prompt, and
+//! [`Vocabulary::correct`] fixes near-misses in the output.
+
+/// Trimmed terms, without case-insensitive duplicates.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Vocabulary {
+    terms: Vec<String>,
+}
+
+impl Vocabulary {
+    pub fn new<I, S>(terms: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut seen = std::collections::HashSet::new();
+        let terms = terms
+            .into_iter()
+            .filter_map(|term| {
+                let term = term.as_ref().trim();
+                (!term.is_empty() && seen.insert(term.to_lowercase())).then(|| term.to_string())
+            })
+            .collect();
+        Self { terms }
+    }
+
+    /// Parses a comma-separated list such as `"Glimpse, FluidAudio, Kubernetes"`.
+    pub fn parse(list: &str) -> Self {
+        Self::new(list.split(','))
+    }
+
+    pub fn terms(&self) -> &[String] {
+        &self.terms
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty()
+    }
+
+    /// A prompt listing every term, for engines that are biased through their prompt.
+    pub fn prompt(&self) -> Option<String> {
+        self.prompt_within(usize::MAX, |_| 0)
+    }
+
+    /// Like [`Vocabulary::prompt`], but only with the leading terms whose prompt stays
+    /// within `max_tokens` as measured by `count_tokens`.
+    pub fn prompt_within(
+        &self,
+        max_tokens: usize,
+        count_tokens: impl Fn(&str) -> usize,
+    ) -> Option<String> {
+        let mut included = 0;
+        for count in 1..=self.terms.len() {
+            if count_tokens(&glossary(&self.terms[..count])) > max_tokens {
+                break;
+            }
+            included = count;
+        }
+        (included > 0).then(|| glossary(&self.terms[..included]))
+    }
+
+    /// Replaces words that are close misspellings of a term with the term itself, e.g.
+    /// "Kubernetis" with "Kubernetes" or "fluid audio" with "FluidAudio". A term never
+    /// spans more words than it has itself, counting camel-case parts. Short terms are
+    /// only fixed when the words match apart from case and punctuation.
+    pub fn correct(&self, text: &str) -> String {
+        if self.terms.is_empty() {
+            return text.to_string();
+        }
+        let words = split_words(text);
+        let mut output = String::with_capacity(text.len());
+        output.push_str(&text[..words.first().map_or(text.len(), |word| word.start)]);
+
+        let mut index = 0;
+        while index < words.len() {
+            let replacement = self.best_match(&words[index..]);
+            let (term, span) = match replacement {
+                Some((term, span)) => (Some(term), span),
+                None => (None, 1),
+            };
+            let first = &words[index];
+            let last = &words[index + span - 1];
+            match term {
+                Some(term) => {
+                    output.push_str(first.prefix);
+                    output.push_str(term);
+                    output.push_str(last.suffix);
+                }
+                None => output.push_str(&text[first.start..first.core_end + first.suffix.len()]),
+            }
+            output.push_str(last.trailing);
+            index += span;
+        }
+        output
+    }
+
+    /// The closest term for the words at the start of `words`, with how many words it covers.
+    /// On equal distance the shorter span wins, so a neighbouring word is only absorbed
+    /// when that makes the match strictly closer.
+    fn best_match(&self, words: &[Word<'_>]) -> Option<(&str, usize)> {
+        let mut best: Option<(&str, usize, usize)> = None;
+        for term in &self.terms {
+            let target = normalize(term);
+            if target.is_empty() {
+                continue;
+            }
+            let allowed = allowed_distance(target.chars().count());
+            for span in 1..=term_word_count(term).min(words.len()) {
+                let candidate = words[..span]
+                    .iter()
+                    .map(|word| normalize(word.core))
+                    .collect::<String>();
+                let distance = edit_distance(&candidate, &target);
+                if distance > allowed {
+                    continue;
+                }
+                let better = best.map_or(true, |(_, best_span, best_distance)| {
+                    (distance, span) < (best_distance, best_span)
+                });
+                if better {
+                    best = Some((term, span, distance));
+                }
+            }
+        }
+        best.map(|(term, span, _)| (term, span))
+    }
+}
+
+fn glossary(terms: &[String]) -> String {
+    format!("Glossary: {}.", terms.join(", "))
+}
+
+/// Words in `term`, where each camel-case part such as the "Audio" in "FluidAudio" counts.
+fn term_word_count(term: &str) -> usize {
+    term.split_whitespace()
+        .map(|word| {
+            let chars = word.chars().collect::<Vec<_>>();
+            1 + chars
+                .windows(2)
+                .filter(|pair| pair[0].is_lowercase() && pair[1].is_uppercase())
+                .count()
+        })
+        .sum()
+}
+
+fn allowed_distance(length: usize) -> usize {
+    match length {
+        0..=4 => 0,
+        5..=7 => 1,
+        _ => 2,
+    }
+}
+
+/// Lowercase letters and digits only, so case, spacing and punctuation never count.
+fn normalize(text: &str) -> String {
+    text.chars()
+        .filter(|character| character.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+fn edit_distance(left: &str, right: &str) -> usize {
+    let right = right.chars().collect::<Vec<_>>();
+    let mut previous = (0..=right.len()).collect::<Vec<_>>();
+    for (row, left_char) in left.chars().enumerate() {
+        let mut current = vec![row + 1; right.len() + 1];
+        for (column, right_char) in right.iter().enumerate() {
+            let substitution = previous[column] + usize::from(left_char != *right_char);
+            current[column + 1] = substitution
+                .min(previous[column + 1] + 1)
+                .min(current[column] + 1);
+        }
+        previous = current;
+    }
+    previous[right.len()]
+}
+
+struct Word<'a> {
+    /// Byte offset of `prefix` in the text.
+    start: usize,
+    core_end: usize,
+    prefix: &'a str,
+    core: &'a str,
+    suffix: &'a str,
+    /// Whitespace up to the next word.
+    trailing: &'a str,
+}
+
+fn split_words(text: &str) -> Vec<Word<'_>> {
+    let mut words: Vec<Word<'_>> = Vec::new();
+    let mut offset = 0;
+    for token in text.split_inclusive(char::is_whitespace) {
+        let start = offset;
+        offset += token.len();
+        let body = token.trim_end();
+        if body.is_empty() {
+            // Runs of whitespace belong to the previous word.
+            if let Some(word) = words.last_mut() {
+                word.trailing = &text[word.core_end + word.suffix.len()..offset];
+            }
+            continue;
+        }
+        let core_start = body
+            .find(|character: char| character.is_alphanumeric())
+            .unwrap_or(body.len());
+        let core_end = body
+            .rfind(|character: char| character.is_alphanumeric())
+            .map_or(core_start, |index| {
+                index + body[index..].chars().next().map_or(0, char::len_utf8)
+            });
+        words.push(Word {
+            start,
+            core_end: start + core_end,
+            prefix: &body[..core_start],
+            core: &body[core_start..core_end],
+            suffix: &body[core_end..],
+            trailing: &token[body.len()..],
+        });
+    }
+    words
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Vocabulary;
+
+    #[test]
+    fn deduplicates_and_trims_terms() {
+        let vocabulary = Vocabulary::parse(" Glimpse, glimpse ,, Kubernetes");
+
+        assert_eq!(vocabulary.terms(), ["Glimpse", "Kubernetes"]);
+    }
+
+    #[test]
+    fn prompt_stops_at_the_token_budget() {
+        let vocabulary = Vocabulary::new(["Glimpse", "FluidAudio", "Kubernetes"]);
+        let words = |prompt: &str| prompt.split_whitespace().count();
+
+        assert_eq!(
+            vocabulary.prompt_within(3, words).as_deref(),
+            Some("Glossary: Glimpse, FluidAudio.")
+        );
+        assert_eq!(vocabulary.prompt_within(0, words), None);
+        assert_eq!(
+            vocabulary.prompt().as_deref(),
+            Some("Glossary: Glimpse, FluidAudio, Kubernetes.")
+        );
+    }
+
+    #[test]
+    fn corrects_near_misses_and_keeps_punctuation() {
+        let vocabulary = Vocabulary::new(["Kubernetes", "FluidAudio", "Glimpse", "Rust"]);
+
+        assert_eq!(
+            vocabulary.correct("We deploy on kubernetis, using fluid audio and glimse!"),
+            "We deploy on Kubernetes, using FluidAudio and Glimpse!"
+        );
+    }
+
+    #[test]
+    fn never_swallows_the_following_word() {
+        let vocabulary = Vocabulary::new(["Kubernetes", "Postgres"]);
+
+        assert_eq!(
+            vocabulary.correct("Deploy Kubernete is easy."),
+            "Deploy Kubernetes is easy."
+        );
+        assert_eq!(vocabulary.correct("Postgre is fast"), "Postgres is fast");
+    }
+
+    #[test]
+    fn leaves_short_and_unrelated_words_alone() {
+        let vocabulary = Vocabulary::new(["Rust", "Glimpse"]);
+
+        assert_eq!(
+            vocabulary.correct("  I trust the rust  code.\n"),
+            "  I trust the Rust  code.\n"
+        );
+        assert_eq!(Vocabulary::default().correct("unchanged"), "unchanged");
+    }
+}
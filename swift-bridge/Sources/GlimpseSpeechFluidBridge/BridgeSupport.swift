@@ -8,6 +8,7 @@ enum BridgeError: Error, Equatable {
     case unsupportedPlatform(String)
     case modelNotFound(String)
     case fluidUnavailable(String)
+    case networkDisabled(String)
     case internalFailure(String)
 
     var code: String {
@@ -22,6 +23,8 @@ enum BridgeError: Error, Equatable {
             return "model_not_found"
         case .fluidUnavailable:
             return "fluid_unavailable"
+        case .networkDisabled:
+            return "network_disabled"
         case .internalFailure:
             return "internal_failure"
         }
@@ -34,6 +37,7 @@ enum BridgeError: Error, Equatable {
              let .unsupportedPlatform(text),
              let .modelNotFound(text),
              let .fluidUnavailable(text),
+             let .networkDisabled(text),
              let .internalFailure(text):
             return text
         }
@@ -47,17 +51,76 @@ func toBridgeError(_ error: Error) -> BridgeError {
     return .internalFailure(String(describing: error))
 }
 
+/// Log severities, numbered like Rust's `log::Level`.
+enum BridgeLogLevel: Int32 {
+    case error = 1
+    case warn = 2
+    case info = 3
+    case debug = 4
+    case trace = 5
+}
+
+/// Receives the level, a category such as `transcribe` or `vocabulary`, and the message.
+typealias BridgeLogCallback = @convention(c) (Int32, UnsafePointer<CChar>?, UnsafePointer<CChar>?) -> Void
+
+/// Sends diagnostics to the host's callback, or to NSLog until one is registered.
+enum BridgeLog {
+    private static let lock = NSLock()
+    nonisolated(unsafe) private static var callback: BridgeLogCallback?
+
+    static func setCallback(_ newCallback: BridgeLogCallback?) {
+        lock.lock()
+        callback = newCallback
+        lock.unlock()
+    }
+
+    static func log(_ level: BridgeLogLevel, _ category: String, _ message: String) {
+        lock.lock()
+        let current = callback
+        lock.unlock()
+
+        guard let current else {
+            NSLog("[GlimpseSpeechFluidBridge] \(message)")
+            return
+        }
+        category.withCString { categoryPointer in
+            message.withCString { messagePointer in
+                current(level.rawValue, categoryPointer, messagePointer)
+            }
+        }
+    }
+}
+
 struct BridgeConfig: Codable, Equatable {
     let schemaVersion: Int
     let asrModelDir: String
     let diarizationModelDir: String?
     let runtimeMacOSMajor: Int
+    /// Whether missing tokenizer files may be downloaded from huggingface.co.
+    var allowNetwork: Bool = false
+    /// `v2` or `v3` to load only that Parakeet version; `nil` probes v3, then v2.
+    var modelVersion: String? = nil
 
     enum CodingKeys: String, CodingKey {
         case schemaVersion = "schema_version"
         case asrModelDir = "asr_model_dir"
         case diarizationModelDir = "diarization_model_dir"
         case runtimeMacOSMajor = "runtime_macos_major"
+        case allowNetwork = "allow_network"
+        case modelVersion = "model_version"
+    }
+}
+
+extension BridgeConfig {
+    // Declared in an extension to keep the memberwise initializer.
+    init(from decoder: Decoder) throws {
+        let container = try decoder.container(keyedBy: CodingKeys.self)
+        schemaVersion = try container.decode(Int.self, forKey: .schemaVersion)
+        asrModelDir = try container.decode(String.self, forKey: .asrModelDir)
+        diarizationModelDir = try container.decodeIfPresent(String.self, forKey: .diarizationModelDir)
+        runtimeMacOSMajor = try container.decode(Int.self, forKey: .runtimeMacOSMajor)
+        allowNetwork = try container.decodeIfPresent(Bool.self, forKey: .allowNetwork) ?? false
+        modelVersion = try container.decodeIfPresent(String.self, forKey: .modelVersion)
     }
 }
 
@@ -111,12 +174,26 @@ struct BridgeWord: Codable, Equatable {
     }
 }
 
+struct BridgeToken: Codable, Equatable {
+    let startMs: UInt64
+    let endMs: UInt64
+    let text: String
+
+    enum CodingKeys: String, CodingKey {
+        case startMs = "start_ms"
+        case endMs = "end_ms"
+        case text
+    }
+}
+
 struct BridgeTranscript: Codable, Equatable {
     let schemaVersion: Int
     let engine: String
     let text: String
     let segments: [BridgeSegment]
     let words: [BridgeWord]?
+    let tokens: [BridgeToken]?
+    var warnings: [BridgeWarning] = []
 
     enum CodingKeys: String, CodingKey {
         case schemaVersion = "schema_version"
@@ -124,9 +201,18 @@ struct BridgeTranscript: Codable, Equatable {
         case text
         case segments
         case words
+        case tokens
+        case warnings
     }
 }
 
+/// Something that degraded a transcript without failing it, e.g.
+/// `vocabulary_boosting_disabled`.
+struct BridgeWarning: Codable, Equatable {
+    let code: String
+    let message: String
+}
+
 struct BridgeSpeakerTurn: Codable, Equatable {
     let startMs: UInt64
     let endMs: UInt64
@@ -234,6 +320,10 @@ enum ModelPaths {
             )
         }
 
+        if let version = config.modelVersion, !["v2", "v3"].contains(version) {
+            throw BridgeError.invalidConfig("model_version must be v2 or v3, got \(version)")
+        }
+
         try validateDirectory(path: config.asrModelDir, label: "ASR")
         if let diar = config.diarizationModelDir, !diar.isEmpty {
             try validateDirectory(path: diar, label: "diarization")
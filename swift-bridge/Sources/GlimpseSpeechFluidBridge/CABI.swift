@@ -58,23 +58,50 @@ private func resolvePayloadBytes(
     return Data(bytes: bytes, count: length)
 }
 
+private func createHandle(
+    _ configBytes: UnsafePointer<UInt8>?,
+    _ configLen: Int
+) throws -> UnsafeMutableRawPointer {
+    guard let configBytes, configLen > 0 else {
+        throw BridgeError.invalidPayload("create config payload is empty")
+    }
+
+    let data = Data(bytes: configBytes, count: configLen)
+    let config = try Serialization.decodeConfig(from: data)
+    let facade = try FluidFacade(config: config)
+    let handle = BridgeHandle(facade: facade)
+    return Unmanaged.passRetained(handle).toOpaque()
+}
+
 @_cdecl("glimpse_fluid_create")
 public func glimpse_fluid_create(
     _ configBytes: UnsafePointer<UInt8>?,
     _ configLen: Int
 ) -> UnsafeMutableRawPointer? {
     do {
-        guard let configBytes, configLen > 0 else {
-            throw BridgeError.invalidPayload("create config payload is empty")
-        }
-
-        let data = Data(bytes: configBytes, count: configLen)
-        let config = try Serialization.decodeConfig(from: data)
-        let facade = try FluidFacade(config: config)
-        let handle = BridgeHandle(facade: facade)
-        return Unmanaged.passRetained(handle).toOpaque()
+        return try createHandle(configBytes, configLen)
+    } catch {
+        BridgeLog.log(.error, "create", "create failed: \(error)")
+        return nil
+    }
+}
+
+/// Like `glimpse_fluid_create`, but on failure stores an error envelope in `outError`,
+/// to be released with `glimpse_fluid_free_buffer`.
+@_cdecl("glimpse_fluid_create_with_error")
+public func glimpse_fluid_create_with_error(
+    _ configBytes: UnsafePointer<UInt8>?,
+    _ configLen: Int,
+    _ outError: UnsafeMutablePointer<UnsafeMutablePointer<UInt8>?>?,
+    _ outErrorLen: UnsafeMutablePointer<Int>?
+) -> UnsafeMutableRawPointer? {
+    outError?.pointee = nil
+    outErrorLen?.pointee = 0
+    do {
+        return try createHandle(configBytes, configLen)
     } catch {
-        NSLog("[GlimpseSpeechFluidBridge] create failed: \(error)")
+        BridgeLog.log(.error, "create", "create failed: \(error)")
+        outError?.pointee = errorBuffer(error, outLen: outErrorLen)
         return nil
     }
 }
@@ -107,7 +134,7 @@ public func glimpse_fluid_transcribe_wav(
         let transcript = try handle.facade.transcribe(wavPath: path, options: options)
         return buffer(from: Serialization.encodeSuccess(transcript), outLen: outLen)
     } catch {
-        NSLog("[GlimpseSpeechFluidBridge] transcribe failed: \(error)")
+        BridgeLog.log(.error, "transcribe", "transcribe failed: \(error)")
         return errorBuffer(error, outLen: outLen)
     }
 }
@@ -132,11 +159,17 @@ public func glimpse_fluid_diarize_wav(
         let diarization = try handle.facade.diarize(wavPath: path, options: options)
         return buffer(from: Serialization.encodeSuccess(diarization), outLen: outLen)
     } catch {
-        NSLog("[GlimpseSpeechFluidBridge] diarize failed: \(error)")
+        BridgeLog.log(.error, "diarize", "diarize failed: \(error)")
         return errorBuffer(error, outLen: outLen)
     }
 }
 
+/// Routes bridge diagnostics to `callback` instead of NSLog; null restores NSLog.
+@_cdecl("glimpse_fluid_set_log_callback")
+public func glimpse_fluid_set_log_callback(_ callback: BridgeLogCallback?) {
+    BridgeLog.setCallback(callback)
+}
+
 @_cdecl("glimpse_fluid_free_buffer")
 public func glimpse_fluid_free_buffer(_ ptr: UnsafeMutablePointer<UInt8>?, _ len: Int) {
     guard let ptr, len > 0 else {
@@ -14,6 +14,8 @@ final class FluidFacade: @unchecked Sendable {
     private var cachedCtcModels: CtcModels?
     private var cachedCtcTokenizer: CtcTokenizer?
     private var configuredVocabularyTerms: [String] = []
+    /// Warnings for the transcription in progress.
+    private var pendingWarnings: [BridgeWarning] = []
     private let bridgeLock = NSLock()
     #endif
 
@@ -22,7 +24,10 @@ final class FluidFacade: @unchecked Sendable {
         self.config = config
 
         #if canImport(FluidAudio)
-        let asrResolution = try Self.resolveAsrDirectory(from: config.asrModelDir)
+        let asrResolution = try Self.resolveAsrDirectory(
+            from: config.asrModelDir,
+            version: Self.asrModelVersion(from: config.modelVersion)
+        )
 
         let ctcResolution = Self.resolveCtcDirectory(near: asrResolution.directory)
         self.ctcModelDirectory = ctcResolution?.directory
@@ -49,28 +54,31 @@ final class FluidFacade: @unchecked Sendable {
         #if canImport(FluidAudio)
         let wavURL = try Self.existingFileURL(path: wavPath, label: "wav")
         let wavName = wavURL.lastPathComponent
-        NSLog(
-            "[GlimpseSpeechFluidBridge] transcribe start wav=\(wavName) vocab_terms=\(options.vocabulary.count) timestamps=\(options.timestamps)"
+        BridgeLog.log(
+            .debug,
+            "transcribe",
+            "transcribe start wav=\(wavName) vocab_terms=\(options.vocabulary.count) timestamps=\(options.timestamps)"
         )
         return try bridgeLock.withLock {
             try Self.blocking {
+                self.pendingWarnings = []
                 do {
                     try await self.configureVocabularyBoosting(vocabulary: options.vocabulary)
+                } catch let error as BridgeError where error.code == "network_disabled" {
+                    // The caller asked for boosting that needs a download they did not allow.
+                    throw error
                 } catch {
                     // Vocabulary boosting is optional; fall back to plain ASR if CTC setup fails.
-                    self.asrManager.disableVocabularyBoosting()
-                    self.configuredVocabularyTerms = []
-                    NSLog("[GlimpseSpeechFluidBridge] vocabulary boosting unavailable: \(error)")
+                    self.disableVocabularyBoosting("vocabulary", "vocabulary boosting unavailable: \(error)")
                 }
                 let result: ASRResult
                 do {
                     result = try await self.asrManager.transcribe(wavURL, source: .system)
                 } catch {
                     if Self.isTokenizerMissingError(error) {
-                        self.asrManager.disableVocabularyBoosting()
-                        self.configuredVocabularyTerms = []
-                        NSLog(
-                            "[GlimpseSpeechFluidBridge] tokenizer unavailable during decode; retrying without vocabulary boosting: \(error)"
+                        self.disableVocabularyBoosting(
+                            "tokenizer",
+                            "tokenizer unavailable during decode; retrying without vocabulary boosting: \(error)"
                         )
                         result = try await self.asrManager.transcribe(wavURL, source: .system)
                     } else {
@@ -78,10 +86,16 @@ final class FluidFacade: @unchecked Sendable {
                     }
                 }
                 let tokenTimingCount = result.tokenTimings?.count ?? 0
-                NSLog(
-                    "[GlimpseSpeechFluidBridge] transcribe result text_len=\(result.text.count) token_timings=\(tokenTimingCount)"
+                BridgeLog.log(
+                    .debug,
+                    "transcribe",
+                    "transcribe result text_len=\(result.text.count) token_timings=\(tokenTimingCount)"
+                )
+                return Self.toBridgeTranscript(
+                    from: result,
+                    timestampPreference: options.timestamps,
+                    warnings: self.pendingWarnings
                 )
-                return Self.toBridgeTranscript(from: result, timestampPreference: options.timestamps)
             }
         }
         #else
@@ -138,7 +152,22 @@ extension FluidFacade {
         let variant: CtcModelVariant
     }
 
-    static func resolveAsrDirectory(from configuredPath: String) throws -> AsrResolution {
+    static func asrModelVersion(from wireValue: String?) -> AsrModelVersion? {
+        switch wireValue {
+        case "v2":
+            return .v2
+        case "v3":
+            return .v3
+        default:
+            return nil
+        }
+    }
+
+    /// Probes v3 before v2, or only `version` when one was requested.
+    static func resolveAsrDirectory(
+        from configuredPath: String,
+        version: AsrModelVersion? = nil
+    ) throws -> AsrResolution {
         let root = URL(fileURLWithPath: configuredPath, isDirectory: true).standardizedFileURL
         let v3 = Repo.parakeet.folderName
         let v2 = Repo.parakeetV2.folderName
@@ -148,7 +177,7 @@ extension FluidFacade {
             (root, .v2),
             (root.appendingPathComponent(v3, isDirectory: true), .v3),
             (root.appendingPathComponent(v2, isDirectory: true), .v2),
-        ]
+        ].filter { version == nil || $0.version == version }
 
         for candidate in candidates {
             let repoDirectory = asrRepositoryDirectory(from: candidate.directory, version: candidate.version)
@@ -157,8 +186,16 @@ extension FluidFacade {
             }
         }
 
+        let expected: String = switch version {
+        case .v2:
+            "a '\(v2)'"
+        case .v3:
+            "a '\(v3)'"
+        default:
+            "a '\(v3)' or '\(v2)'"
+        }
         throw BridgeError.modelNotFound(
-            "ASR models not found in \(configuredPath). Expected a '\(v3)' or '\(v2)' directory with \(ModelNames.ASR.requiredModels.joined(separator: ", ")) and \(ModelNames.ASR.vocabularyFile)"
+            "ASR models not found in \(configuredPath). Expected \(expected) directory with \(ModelNames.ASR.requiredModels.joined(separator: ", ")) and \(ModelNames.ASR.vocabularyFile)"
         )
     }
 
@@ -224,6 +261,14 @@ extension FluidFacade {
         return baseDirectory
     }
 
+    /// Turns boosting off and reports why in the transcript of the current request.
+    private func disableVocabularyBoosting(_ category: String, _ reason: String) {
+        asrManager.disableVocabularyBoosting()
+        configuredVocabularyTerms = []
+        pendingWarnings.append(BridgeWarning(code: "vocabulary_boosting_disabled", message: reason))
+        BridgeLog.log(.warn, category, reason)
+    }
+
     func configureVocabularyBoosting(vocabulary: [String]) async throws {
         let terms = Self.normalizeVocabularyTerms(vocabulary)
         guard !terms.isEmpty else {
@@ -242,8 +287,7 @@ extension FluidFacade {
         else {
             // Vocabulary boosting is best-effort. If CTC models are not present,
             // keep transcription working without failing the request.
-            asrManager.disableVocabularyBoosting()
-            configuredVocabularyTerms = []
+            disableVocabularyBoosting("vocabulary", "no CTC model found next to the ASR model")
             return
         }
 
@@ -253,17 +297,21 @@ extension FluidFacade {
             to: tokenizerDirectory
         )
         if !Self.hasTokenizerFiles(in: tokenizerDirectory) {
+            guard config.allowNetwork else {
+                throw BridgeError.networkDisabled(
+                    "Tokenizer files for vocabulary boosting are missing (\(Self.requiredTokenizerFiles.joined(separator: ", ")) next to \(ctcModelDirectory.path)) and network downloads are disabled. Add the files, set allow_network, or transcribe without vocabulary terms."
+                )
+            }
             do {
                 try await Self.maybeDownloadTokenizerFiles(to: tokenizerDirectory)
             } catch {
-                NSLog("[GlimpseSpeechFluidBridge] tokenizer download failed: \(error)")
+                BridgeLog.log(.warn, "tokenizer", "tokenizer download failed: \(error)")
             }
         }
         guard Self.hasTokenizerFiles(in: tokenizerDirectory) else {
-            asrManager.disableVocabularyBoosting()
-            configuredVocabularyTerms = []
-            NSLog(
-                "[GlimpseSpeechFluidBridge] tokenizer files missing in cache directory; disabling vocabulary boosting"
+            disableVocabularyBoosting(
+                "tokenizer",
+                "tokenizer files missing in cache directory; disabling vocabulary boosting"
             )
             return
         }
@@ -275,9 +323,10 @@ extension FluidFacade {
         let tokenizer = try await loadCtcTokenizer(from: tokenizerDirectory)
         let customTerms = Self.tokenizedVocabularyTerms(terms, tokenizer: tokenizer)
         guard !customTerms.isEmpty else {
-            asrManager.disableVocabularyBoosting()
-            configuredVocabularyTerms = []
-            NSLog("[GlimpseSpeechFluidBridge] vocabulary terms produced no CTC tokens; disabling vocabulary boosting")
+            disableVocabularyBoosting(
+                "vocabulary",
+                "vocabulary terms produced no CTC tokens; disabling vocabulary boosting"
+            )
             return
         }
 
@@ -291,11 +340,11 @@ extension FluidFacade {
         variant ctcModelVariant: CtcModelVariant
     ) async throws -> CtcModels {
         if let cachedCtcModels {
-            NSLog("[GlimpseSpeechFluidBridge] using cached CTC models")
+            BridgeLog.log(.debug, "vocabulary", "using cached CTC models")
             return cachedCtcModels
         }
 
-        NSLog("[GlimpseSpeechFluidBridge] loading CTC models")
+        BridgeLog.log(.info, "vocabulary", "loading CTC models")
         let loaded = try await CtcModels.loadDirect(
             from: ctcModelDirectory,
             variant: ctcModelVariant
@@ -324,7 +373,7 @@ extension FluidFacade {
         for term in terms {
             let tokenIds = tokenizer.encode(term)
             guard !tokenIds.isEmpty else {
-                NSLog("[GlimpseSpeechFluidBridge] skipping un-tokenizable vocabulary term: \(term)")
+                BridgeLog.log(.warn, "vocabulary", "skipping un-tokenizable vocabulary term: \(term)")
                 continue
             }
             tokenizedTerms.append(
@@ -387,7 +436,7 @@ extension FluidFacade {
         }
 
         if hasTokenizerFiles(in: destination) {
-            NSLog("[GlimpseSpeechFluidBridge] installed tokenizer files in cache directory")
+            BridgeLog.log(.info, "tokenizer", "installed tokenizer files in cache directory")
         }
     }
 
@@ -429,7 +478,7 @@ extension FluidFacade {
         }
 
         if hasTokenizerFiles(in: destination) {
-            NSLog("[GlimpseSpeechFluidBridge] downloaded tokenizer files to cache directory")
+            BridgeLog.log(.info, "tokenizer", "downloaded tokenizer files to cache directory")
         }
     }
 
@@ -487,9 +536,11 @@ extension FluidFacade {
 
     static func toBridgeTranscript(
         from result: ASRResult,
-        timestampPreference: String
+        timestampPreference: String,
+        warnings: [BridgeWarning] = []
     ) -> BridgeTranscript {
         let includeWords = timestampPreference != "segments_only"
+        let includeTokens = timestampPreference == "tokens"
         let words = toBridgeWords(from: result.tokenTimings)
 
         let resolvedText: String = {
@@ -524,7 +575,9 @@ extension FluidFacade {
             engine: "fluid",
             text: resolvedText,
             segments: segments,
-            words: wordsWithSegment
+            words: wordsWithSegment,
+            tokens: includeTokens ? toBridgeTokens(from: result.tokenTimings) : nil,
+            warnings: warnings
         )
     }
 
@@ -642,6 +695,42 @@ extension FluidFacade {
         return words.isEmpty ? nil : words
     }
 
+    /// Sub-word pieces with their own timings. A piece that starts a word gets a leading
+    /// space instead of the tokenizer's boundary marker, so joining the pieces rebuilds the text.
+    static func toBridgeTokens(from timings: [TokenTiming]?) -> [BridgeToken]? {
+        guard let timings, !timings.isEmpty else {
+            return nil
+        }
+
+        var tokens: [BridgeToken] = []
+        tokens.reserveCapacity(timings.count)
+
+        for timing in timings {
+            let cleanedToken = stripWordBoundaryPrefix(timing.token)
+                .trimmingCharacters(in: .whitespacesAndNewlines)
+            if cleanedToken.isEmpty {
+                continue
+            }
+
+            let startMs = toMilliseconds(timing.startTime)
+            var endMs = toMilliseconds(timing.endTime)
+            if endMs <= startMs {
+                endMs = startMs + 1
+            }
+
+            let startsWord = tokenStartsWordBoundary(timing.token) || tokens.isEmpty
+            tokens.append(
+                BridgeToken(
+                    startMs: startMs,
+                    endMs: endMs,
+                    text: startsWord ? " " + cleanedToken : cleanedToken
+                )
+            )
+        }
+
+        return tokens.isEmpty ? nil : tokens
+    }
+
     static func toBridgeSegments(from words: [BridgeWord]) -> [BridgeSegment] {
         let gapBreakMs: UInt64 = 750
         let maxSegmentMs: UInt64 = 6_000
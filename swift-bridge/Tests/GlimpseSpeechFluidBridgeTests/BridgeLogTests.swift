@@ -0,0 +1,29 @@
+import XCTest
+@testable import GlimpseSpeechFluidBridge
+
+nonisolated(unsafe) private var receivedRecords: [(Int32, String, String)] = []
+
+final class BridgeLogTests: XCTestCase {
+    override func tearDown() {
+        glimpse_fluid_set_log_callback(nil)
+        receivedRecords = []
+        super.tearDown()
+    }
+
+    func testRecordsReachTheRegisteredCallback() {
+        glimpse_fluid_set_log_callback { level, category, message in
+            receivedRecords.append((
+                level,
+                category.map { String(cString: $0) } ?? "",
+                message.map { String(cString: $0) } ?? ""
+            ))
+        }
+
+        BridgeLog.log(.warn, "vocabulary", "vocabulary boosting unavailable")
+
+        XCTAssertEqual(receivedRecords.count, 1)
+        XCTAssertEqual(receivedRecords.first?.0, 2)
+        XCTAssertEqual(receivedRecords.first?.1, "vocabulary")
+        XCTAssertEqual(receivedRecords.first?.2, "vocabulary boosting unavailable")
+    }
+}
@@ -8,6 +8,7 @@ final class ErrorMappingTests: XCTestCase {
         XCTAssertEqual(BridgeError.unsupportedPlatform("x").code, "unsupported_platform")
         XCTAssertEqual(BridgeError.modelNotFound("x").code, "model_not_found")
         XCTAssertEqual(BridgeError.fluidUnavailable("x").code, "fluid_unavailable")
+        XCTAssertEqual(BridgeError.networkDisabled("x").code, "network_disabled")
         XCTAssertEqual(BridgeError.internalFailure("x").code, "internal_failure")
     }
 
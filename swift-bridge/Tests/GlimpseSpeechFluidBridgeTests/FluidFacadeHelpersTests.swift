@@ -43,6 +43,20 @@ final class FluidFacadeHelpersTests: XCTestCase {
         XCTAssertEqual(words?.count, 3)
     }
 
+    func testToBridgeTokensKeepsSubwordPiecesWithWordBoundarySpaces() {
+        let timings = [
+            TokenTiming(token: "▁G", tokenId: 0, startTime: 0.00, endTime: 0.05, confidence: 0.9),
+            TokenTiming(token: "aren", tokenId: 1, startTime: 0.05, endTime: 0.12, confidence: 0.9),
+            TokenTiming(token: "▁", tokenId: 2, startTime: 0.12, endTime: 0.13, confidence: 0.9),
+            TokenTiming(token: "▁here", tokenId: 3, startTime: 0.20, endTime: 0.20, confidence: 0.9),
+        ]
+
+        let tokens = FluidFacade.toBridgeTokens(from: timings)
+        XCTAssertEqual(tokens?.map(\.text), [" G", "aren", " here"])
+        XCTAssertEqual(tokens?.last?.startMs, 200)
+        XCTAssertEqual(tokens?.last?.endMs, 201)
+    }
+
     func testToBridgeSegmentsSplitsLongRunsByPunctuationAndGap() {
         let words = [
             BridgeWord(startMs: 0, endMs: 400, text: "Hello", segmentIndex: nil),
@@ -37,6 +37,24 @@ final class ModelPathsTests: XCTestCase {
         }
     }
 
+    func testValidateRejectsUnknownModelVersion() {
+        let config = BridgeConfig(
+            schemaVersion: 1,
+            asrModelDir: NSTemporaryDirectory(),
+            diarizationModelDir: nil,
+            runtimeMacOSMajor: 14,
+            modelVersion: "v4"
+        )
+
+        XCTAssertThrowsError(try ModelPaths.validate(config: config)) { error in
+            guard case let BridgeError.invalidConfig(message) = error else {
+                XCTFail("expected invalidConfig, got \(error)")
+                return
+            }
+            XCTAssertTrue(message.contains("model_version"))
+        }
+    }
+
     func testValidateRejectsOldMacOSVersion() {
         let config = BridgeConfig(
             schemaVersion: 1,
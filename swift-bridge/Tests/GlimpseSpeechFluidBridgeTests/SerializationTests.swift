@@ -15,6 +15,37 @@ final class SerializationTests: XCTestCase {
         XCTAssertEqual(decoded, config)
     }
 
+    func testDecodeConfigDefaultsToNoNetwork() throws {
+        let data = Data(
+            #"{"schema_version":1,"asr_model_dir":"/tmp/asr","diarization_model_dir":null,"runtime_macos_major":14}"#.utf8
+        )
+        XCTAssertFalse(try Serialization.decodeConfig(from: data).allowNetwork)
+
+        let allowed = Data(
+            #"{"schema_version":1,"asr_model_dir":"/tmp/asr","runtime_macos_major":14,"allow_network":true}"#.utf8
+        )
+        XCTAssertTrue(try Serialization.decodeConfig(from: allowed).allowNetwork)
+    }
+
+    func testEncodeTranscriptWarnings() throws {
+        let transcript = BridgeTranscript(
+            schemaVersion: 1,
+            engine: "fluid",
+            text: "hello",
+            segments: [BridgeSegment(startMs: 0, endMs: 500, text: "hello")],
+            words: nil,
+            tokens: nil,
+            warnings: [BridgeWarning(code: "vocabulary_boosting_disabled", message: "no CTC model")]
+        )
+        let data = Serialization.encodeSuccess(transcript)
+        let json = try JSONSerialization.jsonObject(with: data) as? [String: Any]
+        let payload = json?["data"] as? [String: Any]
+        let warnings = payload?["warnings"] as? [[String: String]]
+
+        XCTAssertEqual(warnings?.first?["code"], "vocabulary_boosting_disabled")
+        XCTAssertEqual(warnings?.first?["message"], "no CTC model")
+    }
+
     func testEncodeErrorEnvelope() throws {
         let data = Serialization.encodeError(.invalidPayload("bad input"))
         let json = try JSONSerialization.jsonObject(with: data) as? [String: Any]
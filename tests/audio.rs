@@ -1,7 +1,7 @@
 use std::path::PathBuf;
 use std::time::{SystemTime, UNIX_EPOCH};
 
-use glimpse_speech::audio::read_wav_samples;
+use glimpse_speech::audio::{decode_wav_bytes, encode_wav_samples, read_wav_samples};
 
 #[test]
 fn reads_pcm16_mono_16khz_wav() {
@@ -23,6 +23,35 @@ fn rejects_non_16khz_wav() {
     assert!(error.to_string().contains("16000"));
 }
 
+#[test]
+fn encoded_wav_round_trips_through_reader() {
+    let bytes = encode_wav_samples(&[0.0, 0.5, -0.5, 2.0]).expect("samples should encode");
+    let nonce = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("clock should be monotonic")
+        .as_nanos();
+    let mut path = std::env::temp_dir();
+    path.push(format!("glimpse-speech-encoded-{nonce}.wav"));
+    std::fs::write(&path, bytes).expect("wav should be written");
+
+    let samples = read_wav_samples(&path).expect("encoded wav should load");
+    let _ = std::fs::remove_file(path);
+
+    assert_eq!(samples.len(), 4);
+    assert!((samples[1] - 0.5).abs() < 1e-3);
+    assert_eq!(samples[3], 1.0);
+}
+
+#[test]
+fn decodes_in_memory_wav() {
+    let bytes = encode_wav_samples(&[0.25, -0.25]).expect("samples should encode");
+    let samples = decode_wav_bytes(&bytes).expect("in-memory wav should decode");
+
+    assert_eq!(samples.len(), 2);
+    assert!((samples[0] - 0.25).abs() < 1e-3);
+    assert!(decode_wav_bytes(b"not a wav").is_err());
+}
+
 fn write_temp_wav(sample_rate: u32, samples: &[i16]) -> PathBuf {
     let nonce = SystemTime::now()
         .duration_since(UNIX_EPOCH)
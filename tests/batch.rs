@@ -0,0 +1,178 @@
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+use glimpse_speech::batch::BatchTranscriber;
+use glimpse_speech::pool::{EnginePool, PoolError, PoolOptions};
+use glimpse_speech::{TranscriptionEngine, TranscriptionResult};
+
+/// Sleeps for `samples.len()` milliseconds and reports the sample count.
+#[derive(Default)]
+struct SleepEngine;
+
+impl TranscriptionEngine for SleepEngine {
+    type InferenceParams = ();
+    type ModelParams = ();
+
+    fn load_model_with_params(
+        &mut self,
+        _model_path: &Path,
+        _params: Self::ModelParams,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        Ok(())
+    }
+
+    fn unload_model(&mut self) {}
+
+    fn transcribe_samples(
+        &mut self,
+        samples: Vec<f32>,
+        _params: Option<Self::InferenceParams>,
+    ) -> Result<TranscriptionResult, Box<dyn std::error::Error>> {
+        thread::sleep(Duration::from_millis(samples.len() as u64));
+        Ok(TranscriptionResult {
+            text: samples.len().to_string(),
+            ..Default::default()
+        })
+    }
+}
+
+fn pool(workers: usize, queue_capacity: usize) -> EnginePool<SleepEngine> {
+    EnginePool::new(
+        PoolOptions {
+            workers,
+            queue_capacity,
+        },
+        || Ok(SleepEngine),
+    )
+    .expect("pool should start")
+}
+
+/// Treats the file name as a number of milliseconds to sleep.
+fn sleep_for_name(
+    engine: &mut SleepEngine,
+    input: &Path,
+) -> Result<TranscriptionResult, Box<dyn std::error::Error>> {
+    let name = input.to_str().unwrap_or_default();
+    if name == "panic" {
+        panic!("engine crashed");
+    }
+    let millis: usize = name.parse()?;
+    engine.transcribe_samples(vec![0.0; millis], None)
+}
+
+fn inputs(names: &[&str]) -> Vec<PathBuf> {
+    names.iter().map(PathBuf::from).collect()
+}
+
+#[test]
+fn streams_results_in_completion_order() {
+    let batch = BatchTranscriber::new(pool(3, 8));
+
+    let finished = batch
+        .run(inputs(&["300", "10", "150"]), sleep_for_name)
+        .map(|item| item.result.unwrap().text)
+        .collect::<Vec<_>>();
+
+    assert_eq!(finished, ["10", "150", "300"]);
+}
+
+#[test]
+fn reports_errors_per_item_and_keeps_going() {
+    let batch = BatchTranscriber::new(pool(2, 8));
+
+    let mut items = batch
+        .run(
+            inputs(&["5", "not-a-number", "panic", "20"]),
+            sleep_for_name,
+        )
+        .map(|item| (item.input, item.result))
+        .collect::<Vec<_>>();
+    items.sort_by(|left, right| left.0.cmp(&right.0));
+
+    assert_eq!(items.len(), 4);
+    assert_eq!(items[0].1.as_ref().unwrap().text, "20");
+    assert_eq!(items[1].1.as_ref().unwrap().text, "5");
+    assert!(items[2].1.is_err());
+    assert!(items[3]
+        .1
+        .as_ref()
+        .unwrap_err()
+        .to_string()
+        .contains("panicked"));
+}
+
+#[test]
+fn skips_inputs_from_a_previous_run() {
+    let batch = BatchTranscriber::new(pool(1, 8)).with_skip_list(["1", "3"]);
+
+    let results = batch.run(inputs(&["1", "2", "3", "4"]), sleep_for_name);
+    assert_eq!(results.skipped(), 2);
+    let mut done = results
+        .map(|item| item.input.display().to_string())
+        .collect::<Vec<_>>();
+    done.sort();
+
+    assert_eq!(done, ["2", "4"]);
+}
+
+#[test]
+fn pool_rejects_work_beyond_its_queue() {
+    let pool = pool(1, 0);
+    let (started, running) = mpsc::channel();
+    let busy = pool
+        .execute(move |engine| {
+            started.send(()).unwrap();
+            engine
+                .transcribe_samples(vec![0.0; 300], None)
+                .unwrap()
+                .text
+        })
+        .expect("the pool is running");
+    running.recv().unwrap();
+
+    assert_eq!(pool.try_execute(|_| ()).err(), Some(PoolError::QueueFull));
+    assert_eq!(busy.recv().unwrap(), "300");
+    assert_eq!(
+        pool.transcribe_samples(vec![0.0; 1], None).unwrap().text,
+        "1"
+    );
+}
+
+#[test]
+fn pool_survives_a_panicking_task() {
+    let pool = pool(1, 4);
+
+    let crashed = pool.execute(|_| panic!("boom")).unwrap();
+    assert!(crashed.recv().is_err());
+    assert_eq!(
+        pool.transcribe_samples(vec![0.0; 2], None).unwrap().text,
+        "2"
+    );
+}
+
+#[test]
+fn pool_fails_to_start_when_an_engine_factory_panics() {
+    let error = EnginePool::<SleepEngine>::new(
+        PoolOptions {
+            workers: 1,
+            queue_capacity: 1,
+        },
+        || panic!("factory exploded"),
+    )
+    .err()
+    .expect("pool should not start");
+
+    assert!(error.to_string().contains("panicked during startup"));
+}
+
+#[test]
+fn pool_fails_to_start_when_an_engine_fails_to_load() {
+    let error =
+        EnginePool::<SleepEngine>::new(PoolOptions::default(), || Err("model not found".into()))
+            .err()
+            .expect("pool should not start");
+
+    assert!(error.to_string().contains("model not found"));
+}
@@ -0,0 +1,130 @@
+#![cfg(feature = "cli")]
+
+use std::path::PathBuf;
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+fn glimpse() -> Command {
+    Command::new(env!("CARGO_BIN_EXE_glimpse"))
+}
+
+#[test]
+fn info_lists_compiled_engines() {
+    let output = glimpse().arg("info").output().expect("glimpse should run");
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.starts_with(&format!("glimpse {}", env!("CARGO_PKG_VERSION"))));
+    assert!(stdout.contains("engines:"));
+}
+
+#[test]
+fn unknown_engine_exits_with_error() {
+    let output = glimpse()
+        .args([
+            "transcribe",
+            "audio.wav",
+            "--engine",
+            "nope",
+            "--model",
+            "m",
+        ])
+        .output()
+        .expect("glimpse should run");
+
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("engine 'nope' is not available"));
+}
+
+#[test]
+fn batch_without_wav_files_fails() {
+    let nonce = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("clock should be monotonic")
+        .as_nanos();
+    let directory: PathBuf = std::env::temp_dir().join(format!("glimpse-cli-empty-{nonce}"));
+    std::fs::create_dir_all(&directory).expect("temp dir should be created");
+
+    let output = glimpse()
+        .arg("batch")
+        .arg(&directory)
+        .args(["--engine", "nope", "--model", "m"])
+        .output()
+        .expect("glimpse should run");
+    let _ = std::fs::remove_dir(&directory);
+
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("no .wav files found"));
+}
+
+#[test]
+fn rejects_unknown_output_format() {
+    let output = glimpse()
+        .args([
+            "transcribe",
+            "audio.wav",
+            "-e",
+            "whisper",
+            "-m",
+            "m",
+            "--format",
+            "csv",
+        ])
+        .output()
+        .expect("glimpse should run");
+
+    assert!(!output.status.success());
+}
+
+#[test]
+fn batch_rejects_inputs_with_the_same_output_file() {
+    let nonce = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("clock should be monotonic")
+        .as_nanos();
+    let directory: PathBuf = std::env::temp_dir().join(format!("glimpse-cli-clash-{nonce}"));
+    for folder in ["a", "b"] {
+        std::fs::create_dir_all(directory.join(folder)).expect("temp dir should be created");
+        std::fs::write(directory.join(folder).join("x.wav"), b"").unwrap();
+    }
+
+    let output = glimpse()
+        .arg("batch")
+        .arg(directory.join("a"))
+        .arg(directory.join("b"))
+        .arg("--output-dir")
+        .arg(directory.join("out"))
+        .args(["--engine", "nope", "--model", "m"])
+        .output()
+        .expect("glimpse should run");
+    let _ = std::fs::remove_dir_all(&directory);
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("would both be written to"), "{stderr}");
+    assert!(!stderr.contains("engine 'nope'"));
+}
+
+#[test]
+fn batch_skips_inputs_with_existing_output() {
+    let nonce = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("clock should be monotonic")
+        .as_nanos();
+    let directory: PathBuf = std::env::temp_dir().join(format!("glimpse-cli-skip-{nonce}"));
+    std::fs::create_dir_all(&directory).expect("temp dir should be created");
+    std::fs::write(directory.join("done.wav"), b"").unwrap();
+    std::fs::write(directory.join("done.txt"), b"already transcribed\n").unwrap();
+
+    let output = glimpse()
+        .arg("batch")
+        .arg(&directory)
+        .args(["--engine", "nope", "--model", "m", "--skip-existing"])
+        .output()
+        .expect("glimpse should run");
+    let _ = std::fs::remove_dir_all(&directory);
+
+    // Nothing is left to transcribe, so no engine is loaded.
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("all 1 files already have output"));
+}
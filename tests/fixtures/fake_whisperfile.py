@@ -0,0 +1,104 @@
+#!/usr/bin/env python3
+"""Stand-in for a whisperfile binary in `--server` mode.
+
+Behaviour is driven by the model file name so tests need no extra flags:
+- `*hang*`: never reports healthy
+- `*crash*`: exits with status 3 before serving
+- `*busy*`: fails to bind its port on the first launch, then serves normally
+Every response echoes the command line and form fields it received.
+"""
+
+import argparse
+import json
+import os
+import sys
+import time
+from http.server import BaseHTTPRequestHandler, ThreadingHTTPServer
+
+parser = argparse.ArgumentParser()
+parser.add_argument("--server", action="store_true")
+parser.add_argument("-m", dest="model", required=True)
+parser.add_argument("--host", required=True)
+parser.add_argument("--port", type=int, required=True)
+parser.add_argument("--gpu", required=True)
+args, extra = parser.parse_known_args()
+
+if "crash" in args.model:
+    sys.exit(3)
+
+if "busy" in args.model and not os.path.exists(args.model + ".tried"):
+    open(args.model + ".tried", "w").close()
+    print("OSError: [Errno 98] Address already in use", file=sys.stderr)
+    sys.exit(1)
+
+STARTED = time.monotonic()
+READY_AFTER_SECS = float("inf") if "hang" in args.model else 0.3
+
+
+def parse_multipart(body, content_type):
+    boundary = content_type.split("boundary=", 1)[1].encode()
+    fields = {}
+    for part in body.split(b"--" + boundary):
+        if b"\r\n\r\n" not in part:
+            continue
+        head, value = part.split(b"\r\n\r\n", 1)
+        value = value[:-2] if value.endswith(b"\r\n") else value
+        name = head.split(b'name="', 1)[1].split(b'"', 1)[0].decode()
+        if b"filename=" in head:
+            fields[name] = "<%d bytes>" % len(value)
+        else:
+            fields[name] = value.decode()
+    return fields
+
+
+class Handler(BaseHTTPRequestHandler):
+    protocol_version = "HTTP/1.1"
+
+    def log_message(self, *_):
+        pass
+
+    def reply(self, status, payload, content_type="application/json"):
+        body = payload.encode() if isinstance(payload, str) else json.dumps(payload).encode()
+        self.send_response(status)
+        self.send_header("Content-Type", content_type)
+        self.send_header("Content-Length", str(len(body)))
+        self.end_headers()
+        self.wfile.write(body)
+
+    def do_GET(self):
+        if self.path != "/health":
+            self.reply(404, {"error": "not found"})
+        elif time.monotonic() - STARTED < READY_AFTER_SECS:
+            self.reply(503, {"status": "loading model"})
+        else:
+            self.reply(200, {"status": "ok"})
+
+    def do_POST(self):
+        if self.path != "/inference":
+            self.reply(404, {"error": "not found"})
+            return
+
+        length = int(self.headers["Content-Length"])
+        fields = parse_multipart(self.rfile.read(length), self.headers["Content-Type"])
+        echo = json.dumps({"gpu": args.gpu, "model": args.model, "fields": fields}, sort_keys=True)
+        response_format = fields.get("response_format", "json")
+        if response_format == "text":
+            self.reply(200, echo + "\n", "text/plain")
+        elif response_format in ("srt", "vtt"):
+            separator = "," if response_format == "srt" else "."
+            header = "" if response_format == "srt" else "WEBVTT\n\n"
+            cue = "00:00:00%s000 --> 00:00:01%s250\n%s\n" % (separator, separator, echo)
+            self.reply(200, header + "1\n" + cue, "text/plain")
+        else:
+            self.reply(
+                200,
+                {
+                    "task": "transcribe",
+                    "language": fields.get("language", "en"),
+                    "duration": 1.0,
+                    "text": " " + echo,
+                    "segments": [{"id": 0, "start": 0.0, "end": 1.0, "text": " " + echo}],
+                },
+            )
+
+ThreadingHTTPServer((args.host, args.port), Handler).serve_forever()
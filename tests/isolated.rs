@@ -0,0 +1,164 @@
+//! Runs without the libtest harness: the test binary doubles as its own engine worker.
+
+use std::path::Path;
+use std::time::Duration;
+
+use glimpse_speech::isolated::{
+    run_worker_if_requested, IsolatedEngine, IsolatedEngineOptions, WorkerError,
+};
+use glimpse_speech::{TranscriptionEngine, TranscriptionResult};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct MockModelParams {
+    label: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+enum MockInferenceParams {
+    Echo,
+    Crash,
+    Hang,
+}
+
+#[derive(Default)]
+struct MockEngine {
+    label: Option<String>,
+}
+
+impl TranscriptionEngine for MockEngine {
+    type InferenceParams = MockInferenceParams;
+    type ModelParams = MockModelParams;
+
+    fn load_model_with_params(
+        &mut self,
+        model_path: &Path,
+        params: Self::ModelParams,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        if model_path.as_os_str() == "missing" {
+            return Err("model not found: missing".into());
+        }
+        self.label = Some(params.label);
+        Ok(())
+    }
+
+    fn unload_model(&mut self) {
+        self.label = None;
+    }
+
+    fn transcribe_samples(
+        &mut self,
+        samples: Vec<f32>,
+        params: Option<Self::InferenceParams>,
+    ) -> Result<TranscriptionResult, Box<dyn std::error::Error>> {
+        let label = self.label.as_deref().ok_or("not loaded")?;
+        match params.unwrap_or(MockInferenceParams::Echo) {
+            MockInferenceParams::Echo => Ok(TranscriptionResult {
+                text: format!("{label}:{}", samples.len()),
+                ..Default::default()
+            }),
+            MockInferenceParams::Crash => std::process::abort(),
+            MockInferenceParams::Hang => loop {
+                std::thread::sleep(Duration::from_secs(60));
+            },
+        }
+    }
+}
+
+fn main() {
+    run_worker_if_requested::<MockEngine>();
+
+    let tests: &[(&str, fn())] = &[
+        (
+            "transcribes_in_worker_process",
+            transcribes_in_worker_process,
+        ),
+        ("forwards_engine_errors", forwards_engine_errors),
+        (
+            "reports_crash_and_restarts_worker",
+            reports_crash_and_restarts_worker,
+        ),
+        (
+            "kills_worker_after_request_timeout",
+            kills_worker_after_request_timeout,
+        ),
+    ];
+
+    for (name, test) in tests {
+        test();
+        println!("test {name} ... ok");
+    }
+}
+
+fn loaded_engine(options: IsolatedEngineOptions) -> IsolatedEngine<MockEngine> {
+    let mut engine = IsolatedEngine::<MockEngine>::with_options(options);
+    engine
+        .load_model_with_params(
+            Path::new("model"),
+            MockModelParams {
+                label: "mock".to_string(),
+            },
+        )
+        .expect("worker should load the model");
+    engine
+}
+
+fn transcribes_in_worker_process() {
+    let mut engine = loaded_engine(IsolatedEngineOptions::default());
+
+    let result = engine
+        .transcribe_samples(vec![0.0; 16], None)
+        .expect("worker should transcribe");
+    assert_eq!(result.text, "mock:16");
+    assert_ne!(engine.worker_id(), Some(std::process::id()));
+}
+
+fn forwards_engine_errors() {
+    let mut engine = IsolatedEngine::<MockEngine>::new();
+    let error = engine
+        .load_model(Path::new("missing"))
+        .expect_err("load failure should be reported");
+
+    assert!(error.to_string().contains("model not found"));
+    assert!(error.downcast_ref::<WorkerError>().is_none());
+}
+
+fn reports_crash_and_restarts_worker() {
+    let mut engine = loaded_engine(IsolatedEngineOptions::default());
+    let first_worker = engine.worker_id();
+
+    let error = engine
+        .transcribe_samples(vec![0.0; 4], Some(MockInferenceParams::Crash))
+        .expect_err("crash should surface as an error");
+    assert!(matches!(
+        error.downcast_ref::<WorkerError>(),
+        Some(WorkerError::WorkerCrashed { .. })
+    ));
+
+    let result = engine
+        .transcribe_samples(vec![0.0; 8], None)
+        .expect("restarted worker should reload the model");
+    assert_eq!(result.text, "mock:8");
+    assert_ne!(engine.worker_id(), first_worker);
+}
+
+fn kills_worker_after_request_timeout() {
+    let mut engine = loaded_engine(IsolatedEngineOptions {
+        request_timeout: Duration::from_millis(300),
+        ..IsolatedEngineOptions::default()
+    });
+
+    let error = engine
+        .transcribe_samples(vec![0.0; 4], Some(MockInferenceParams::Hang))
+        .expect_err("hung worker should time out");
+    assert!(matches!(
+        error.downcast_ref::<WorkerError>(),
+        Some(WorkerError::TimedOut { .. })
+    ));
+    assert_eq!(engine.worker_id(), None);
+
+    let result = engine
+        .transcribe_samples(vec![0.0; 2], None)
+        .expect("worker should restart after a timeout");
+    assert_eq!(result.text, "mock:2");
+}
@@ -0,0 +1,32 @@
+#![cfg(feature = "onnx")]
+
+use std::path::Path;
+
+use glimpse_speech::engines::parakeet::ParakeetModelParams;
+use glimpse_speech::engines::parakeet_onnx::ParakeetOnnxEngine;
+use glimpse_speech::TranscriptionEngine;
+
+#[test]
+fn transcribing_without_a_model_fails() {
+    let mut engine = ParakeetOnnxEngine::new();
+
+    let error = engine
+        .transcribe_samples(vec![0.0; 16_000], None)
+        .expect_err("no model is loaded");
+
+    assert!(error.to_string().contains("Model not loaded"));
+}
+
+#[test]
+fn missing_model_directory_is_reported() {
+    let mut engine = ParakeetOnnxEngine::new();
+
+    let error = engine
+        .load_model_with_params(
+            Path::new("models/does-not-exist"),
+            ParakeetModelParams::int8(),
+        )
+        .expect_err("directory does not exist");
+
+    assert!(error.to_string().contains("Model directory not found"));
+}
@@ -0,0 +1,241 @@
+#![cfg(feature = "remote")]
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::path::Path;
+use std::sync::mpsc;
+use std::thread;
+
+use glimpse_speech::engines::remote::{
+    RemoteEngine, RemoteInferenceParams, RemoteModelParams, TimestampGranularity,
+};
+use glimpse_speech::TranscriptionEngine;
+
+const VERBOSE_JSON: &str = r#"{"task":"transcribe","language":"english","duration":1.2,"text":" hello world","segments":[{"id":0,"start":0.0,"end":1.2,"text":" hello world"}],"words":[{"word":"hello","start":0.0,"end":0.5},{"word":"world","start":0.6,"end":1.2}]}"#;
+
+struct RecordedRequest {
+    request_line: String,
+    headers: Vec<(String, String)>,
+    body: String,
+}
+
+impl RecordedRequest {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// Serves one canned `(status, body)` response per connection, in order. Status `0`
+/// closes the connection without answering.
+fn mock_server(responses: Vec<(u16, &'static str)>) -> (String, mpsc::Receiver<RecordedRequest>) {
+    let listener = TcpListener::bind("127.0.0.1:0").expect("mock server should bind");
+    let base_url = format!("http://{}/v1", listener.local_addr().unwrap());
+    let (sender, receiver) = mpsc::channel();
+
+    thread::spawn(move || {
+        for (status, body) in responses {
+            let (stream, _) = listener.accept().expect("client should connect");
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+            let mut request_line = String::new();
+            reader.read_line(&mut request_line).unwrap();
+            let mut headers = Vec::new();
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                let line = line.trim_end();
+                if line.is_empty() {
+                    break;
+                }
+                if let Some((name, value)) = line.split_once(':') {
+                    headers.push((name.trim().to_string(), value.trim().to_string()));
+                }
+            }
+            let length = headers
+                .iter()
+                .find(|(name, _)| name.eq_ignore_ascii_case("content-length"))
+                .and_then(|(_, value)| value.parse::<usize>().ok())
+                .unwrap_or(0);
+            let mut request_body = vec![0; length];
+            reader.read_exact(&mut request_body).unwrap();
+
+            let mut stream = stream;
+            if status == 0 {
+                let _ = stream.shutdown(std::net::Shutdown::Both);
+                continue;
+            }
+            write!(
+                stream,
+                "HTTP/1.1 {status} Mock\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            )
+            .unwrap();
+            stream.flush().unwrap();
+
+            let _ = sender.send(RecordedRequest {
+                request_line: request_line.trim_end().to_string(),
+                headers,
+                body: String::from_utf8_lossy(&request_body).into_owned(),
+            });
+        }
+    });
+
+    (base_url, receiver)
+}
+
+fn fast_retry_params() -> RemoteModelParams {
+    RemoteModelParams {
+        api_key: Some("sk-test".to_string()),
+        headers: vec![("X-Gateway".to_string(), "lan".to_string())],
+        timeout_secs: 10,
+        max_retries: 2,
+        retry_backoff_ms: 10,
+    }
+}
+
+#[test]
+fn posts_form_and_parses_segments_and_words() {
+    let (base_url, requests) = mock_server(vec![(200, VERBOSE_JSON)]);
+    let mut engine = RemoteEngine::new(base_url);
+    engine
+        .load_model_with_params(Path::new("whisper-1"), fast_retry_params())
+        .expect("remote engine should load");
+
+    let result = engine
+        .transcribe_samples(
+            vec![0.0; 1600],
+            Some(RemoteInferenceParams {
+                language: Some("en".to_string()),
+                prompt: Some("Glimpse".to_string()),
+                temperature: Some(0.0),
+                timestamp_granularities: vec![
+                    TimestampGranularity::Word,
+                    TimestampGranularity::Segment,
+                ],
+            }),
+        )
+        .expect("transcription should succeed");
+
+    assert_eq!(result.text, "hello world");
+    assert_eq!(result.segments.map(|segments| segments.len()), Some(1));
+    let words = result.words.expect("words should be parsed");
+    assert_eq!(words.len(), 2);
+    assert_eq!(words[1].text, "world");
+
+    let request = requests.recv().expect("request should be recorded");
+    assert_eq!(
+        request.request_line,
+        "POST /v1/audio/transcriptions HTTP/1.1"
+    );
+    assert_eq!(request.header("authorization"), Some("Bearer sk-test"));
+    assert_eq!(request.header("x-gateway"), Some("lan"));
+    assert!(request.body.contains("name=\"model\"\r\n\r\nwhisper-1\r\n"));
+    assert!(request.body.contains("name=\"language\"\r\n\r\nen\r\n"));
+    assert!(request
+        .body
+        .contains("name=\"timestamp_granularities[]\"\r\n\r\nword\r\n"));
+    assert!(request.body.contains("filename=\"audio.wav\""));
+}
+
+#[test]
+fn retries_server_errors() {
+    let (base_url, requests) = mock_server(vec![
+        (503, r#"{"error":"warming up"}"#),
+        (500, r#"{"error":"oops"}"#),
+        (200, VERBOSE_JSON),
+    ]);
+    let mut engine = RemoteEngine::new(base_url);
+    engine
+        .load_model_with_params(Path::new("whisper-1"), fast_retry_params())
+        .expect("remote engine should load");
+
+    let result = engine
+        .transcribe_samples(vec![0.0; 160], None)
+        .expect("third attempt should succeed");
+
+    assert_eq!(result.text, "hello world");
+    assert_eq!(requests.iter().take(3).count(), 3);
+}
+
+#[test]
+fn retries_dropped_connections() {
+    let (base_url, requests) = mock_server(vec![(0, ""), (200, VERBOSE_JSON)]);
+    let mut engine = RemoteEngine::new(base_url);
+    engine
+        .load_model_with_params(Path::new("whisper-1"), fast_retry_params())
+        .expect("remote engine should load");
+
+    let result = engine
+        .transcribe_samples(vec![0.0; 160], None)
+        .expect("second attempt should succeed");
+
+    assert_eq!(result.text, "hello world");
+    assert_eq!(requests.iter().take(1).count(), 1);
+}
+
+#[test]
+fn client_errors_are_not_retried() {
+    let (base_url, _requests) = mock_server(vec![(401, r#"{"error":"bad key"}"#)]);
+    let mut engine = RemoteEngine::new(base_url);
+    engine
+        .load_model_with_params(Path::new("whisper-1"), fast_retry_params())
+        .expect("remote engine should load");
+
+    let error = engine
+        .transcribe_samples(vec![0.0; 160], None)
+        .expect_err("401 should fail");
+
+    assert!(error.to_string().contains("HTTP 401"));
+    assert!(error.to_string().contains("bad key"));
+}
+
+#[test]
+fn requires_loaded_model() {
+    let mut engine = RemoteEngine::new("http://127.0.0.1:9/v1");
+    assert!(engine.transcribe_samples(vec![0.0; 16], None).is_err());
+}
+
+#[test]
+fn vocabulary_biases_the_prompt_and_corrects_the_result_on_request() {
+    use glimpse_speech::engines::any::{AnyEngine, AnyInferenceParams};
+
+    let response = r#"{"text":" deployed on kubernetis","segments":[{"start":0.0,"end":1.0,"text":" deployed on kubernetis"}]}"#;
+    let (base_url, requests) = mock_server(vec![(200, response), (200, response)]);
+    let mut engine = RemoteEngine::new(base_url);
+    engine
+        .load_model_with_params(Path::new("whisper-1"), fast_retry_params())
+        .expect("remote engine should load");
+
+    let result = AnyEngine::transcribe_samples(
+        &mut engine,
+        vec![0.0; 1600],
+        &AnyInferenceParams {
+            prompt: Some("Ops standup.".to_string()),
+            vocabulary: vec!["Kubernetes".to_string()],
+            correct_vocabulary: true,
+            ..Default::default()
+        },
+    )
+    .expect("transcription should succeed");
+
+    assert_eq!(result.text, "deployed on Kubernetes");
+    assert_eq!(result.segments.unwrap()[0].text, " deployed on Kubernetes");
+    let request = requests.recv().expect("request should be recorded");
+    assert!(request
+        .body
+        .contains("name=\"prompt\"\r\n\r\nOps standup. Glossary: Kubernetes.\r\n"));
+
+    let uncorrected = AnyEngine::transcribe_samples(
+        &mut engine,
+        vec![0.0; 1600],
+        &AnyInferenceParams {
+            vocabulary: vec!["Kubernetes".to_string()],
+            ..Default::default()
+        },
+    )
+    .expect("transcription should succeed");
+    assert_eq!(uncorrected.text, "deployed on kubernetis");
+}
@@ -0,0 +1,326 @@
+#![cfg(feature = "server")]
+
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpStream};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use glimpse_speech::audio::encode_wav_samples;
+use glimpse_speech::engines::any::{AnyEngine, AnyInferenceParams};
+use glimpse_speech::server::{ServerConfig, TranscriptionServer};
+use glimpse_speech::{TranscriptionResult, TranscriptionSegment, TranscriptionWord};
+
+/// Echoes the request parameters back as the transcript.
+struct EchoEngine {
+    delay: Duration,
+}
+
+impl AnyEngine for EchoEngine {
+    fn transcribe_samples(
+        &mut self,
+        samples: Vec<f32>,
+        params: &AnyInferenceParams,
+    ) -> Result<TranscriptionResult, Box<dyn std::error::Error>> {
+        thread::sleep(self.delay);
+        if params.prompt.as_deref() == Some("fail") {
+            return Err("engine exploded".into());
+        }
+
+        let mut text = format!(
+            "samples={} language={} translate={} temperature={:?} words={}",
+            samples.len(),
+            params.language.as_deref().unwrap_or("auto"),
+            params.translate,
+            params.temperature,
+            params.word_timestamps,
+        );
+        if !params.vocabulary.is_empty() {
+            text.push_str(&format!(" vocabulary={:?}", params.vocabulary));
+        }
+        Ok(TranscriptionResult {
+            text: text.clone(),
+            segments: Some(vec![TranscriptionSegment {
+                start: 0.0,
+                end: 0.5,
+                text,
+                speaker: None,
+            }]),
+            words: params.word_timestamps.then(|| {
+                vec![TranscriptionWord {
+                    start: 0.0,
+                    end: 0.25,
+                    text: "samples".to_string(),
+                }]
+            }),
+            tokens: None,
+            warnings: Vec::new(),
+        })
+    }
+}
+
+fn start_server(config: ServerConfig, delay: Duration) -> SocketAddr {
+    let server = TranscriptionServer::start(ServerConfig { port: 0, ..config }, move || {
+        Ok(Box::new(EchoEngine { delay }) as Box<dyn AnyEngine>)
+    })
+    .expect("server should start");
+    let address = server.local_addr().expect("server should listen on TCP");
+    let server = Arc::new(server);
+    thread::spawn(move || server.run());
+    address
+}
+
+fn post_form(address: SocketAddr, path: &str, fields: &[(&str, &str)]) -> (u16, String) {
+    post_upload(
+        address,
+        path,
+        fields,
+        &encode_wav_samples(&vec![0.0; 8000]).unwrap(),
+    )
+}
+
+fn post_upload(
+    address: SocketAddr,
+    path: &str,
+    fields: &[(&str, &str)],
+    audio: &[u8],
+) -> (u16, String) {
+    let boundary = "glimpse-test-boundary";
+    let mut body = Vec::new();
+    for (name, value) in fields {
+        body.extend_from_slice(
+            format!(
+                "--{boundary}\r\nContent-Disposition: form-data; name=\"{name}\"\r\n\r\n{value}\r\n"
+            )
+            .as_bytes(),
+        );
+    }
+    body.extend_from_slice(
+        format!(
+            "--{boundary}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"a.wav\"\r\nContent-Type: audio/wav\r\n\r\n"
+        )
+        .as_bytes(),
+    );
+    body.extend_from_slice(audio);
+    body.extend_from_slice(format!("\r\n--{boundary}--\r\n").as_bytes());
+
+    let mut stream = TcpStream::connect(address).expect("server should accept connections");
+    write!(
+        stream,
+        "POST {path} HTTP/1.1\r\nHost: localhost\r\nContent-Type: multipart/form-data; boundary={boundary}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        body.len()
+    )
+    .unwrap();
+    stream.write_all(&body).unwrap();
+
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    let status = response[9..12].parse().expect("status code");
+    let body = response
+        .split_once("\r\n\r\n")
+        .map(|(_, body)| body.to_string())
+        .unwrap_or_default();
+    (status, body)
+}
+
+#[test]
+fn transcribes_multipart_upload_as_json() {
+    let address = start_server(ServerConfig::default(), Duration::ZERO);
+
+    let (status, body) = post_form(
+        address,
+        "/v1/audio/transcriptions",
+        &[
+            ("model", "whisper-1"),
+            ("language", "de"),
+            ("temperature", "0.2"),
+        ],
+    );
+
+    assert_eq!(status, 200);
+    let json: serde_json::Value = serde_json::from_str(&body).expect("json body");
+    assert_eq!(
+        json["text"],
+        "samples=8000 language=de translate=false temperature=Some(0.2) words=false"
+    );
+}
+
+#[test]
+fn vocabulary_comes_from_its_own_field_not_the_prompt() {
+    let address = start_server(ServerConfig::default(), Duration::ZERO);
+
+    let (status, body) = post_form(
+        address,
+        "/v1/audio/transcriptions",
+        &[
+            ("prompt", "Hello, world"),
+            ("vocabulary", "Glimpse, Kubernetes"),
+        ],
+    );
+
+    assert_eq!(status, 200);
+    let json: serde_json::Value = serde_json::from_str(&body).expect("json body");
+    assert!(json["text"]
+        .as_str()
+        .unwrap()
+        .ends_with(r#"vocabulary=["Glimpse", "Kubernetes"]"#));
+}
+
+#[test]
+fn verbose_json_includes_requested_granularities() {
+    let address = start_server(ServerConfig::default(), Duration::ZERO);
+
+    let (status, body) = post_form(
+        address,
+        "/v1/audio/transcriptions",
+        &[
+            ("response_format", "verbose_json"),
+            ("timestamp_granularities[]", "word"),
+        ],
+    );
+
+    assert_eq!(status, 200);
+    let json: serde_json::Value = serde_json::from_str(&body).expect("json body");
+    assert_eq!(json["task"], "transcribe");
+    assert_eq!(json["duration"], 0.5);
+    assert_eq!(json["words"][0]["word"], "samples");
+    assert!(json.get("segments").is_none());
+
+    let (_, body) = post_form(
+        address,
+        "/v1/audio/translations",
+        &[("response_format", "verbose_json")],
+    );
+    let json: serde_json::Value = serde_json::from_str(&body).expect("json body");
+    assert_eq!(json["task"], "translate");
+    assert_eq!(json["language"], "english");
+    assert_eq!(json["segments"][0]["end"], 0.5);
+    assert!(json["text"].as_str().unwrap().contains("translate=true"));
+}
+
+#[test]
+fn renders_subtitle_and_text_formats() {
+    let address = start_server(ServerConfig::default(), Duration::ZERO);
+
+    let (_, srt) = post_form(
+        address,
+        "/v1/audio/transcriptions",
+        &[("response_format", "srt")],
+    );
+    assert!(srt.starts_with("1\n00:00:00,000 --> 00:00:00,500\nsamples=8000"));
+
+    let (_, vtt) = post_form(
+        address,
+        "/v1/audio/transcriptions",
+        &[("response_format", "vtt")],
+    );
+    assert!(vtt.starts_with("WEBVTT\n\n00:00:00.000 --> 00:00:00.500\n"));
+
+    let (_, text) = post_form(
+        address,
+        "/v1/audio/transcriptions",
+        &[("response_format", "text")],
+    );
+    assert!(text.starts_with("samples=8000"));
+}
+
+#[test]
+fn rejects_bad_requests_and_reports_engine_errors() {
+    let address = start_server(ServerConfig::default(), Duration::ZERO);
+
+    let (status, body) = post_form(
+        address,
+        "/v1/audio/transcriptions",
+        &[("response_format", "csv")],
+    );
+    assert_eq!(status, 400);
+    assert!(body.contains("invalid_request_error"));
+
+    let (status, body) = post_form(address, "/v1/audio/transcriptions", &[("prompt", "fail")]);
+    assert_eq!(status, 500);
+    assert!(body.contains("engine exploded"));
+
+    let (status, _) = post_form(address, "/v1/audio/nope", &[]);
+    assert_eq!(status, 404);
+}
+
+#[test]
+fn rejects_non_wav_uploads_with_unsupported_media_type() {
+    let address = start_server(ServerConfig::default(), Duration::ZERO);
+
+    let (status, body) = post_upload(
+        address,
+        "/v1/audio/transcriptions",
+        &[],
+        b"ID3\x04\x00\x00\x00\x00\x00\x00 not really an mp3",
+    );
+
+    assert_eq!(status, 415);
+    assert!(body.contains("16 kHz mono 16-bit PCM WAV"));
+}
+
+#[test]
+fn rejects_requests_beyond_the_queue() {
+    let address = start_server(
+        ServerConfig {
+            max_concurrency: 1,
+            queue_capacity: 0,
+            ..Default::default()
+        },
+        Duration::from_millis(600),
+    );
+
+    let busy = thread::spawn(move || post_form(address, "/v1/audio/transcriptions", &[]));
+    thread::sleep(Duration::from_millis(200));
+    let (status, body) = post_form(address, "/v1/audio/transcriptions", &[]);
+
+    assert_eq!(status, 503);
+    assert!(body.contains("at capacity"));
+    assert_eq!(busy.join().unwrap().0, 200);
+}
+
+#[test]
+fn rejects_uploads_beyond_the_queue_before_reading_the_body() {
+    let address = start_server(
+        ServerConfig {
+            max_concurrency: 1,
+            queue_capacity: 0,
+            ..Default::default()
+        },
+        Duration::from_millis(600),
+    );
+
+    let busy = thread::spawn(move || post_form(address, "/v1/audio/transcriptions", &[]));
+    thread::sleep(Duration::from_millis(200));
+
+    // Announce a large upload but never send it: the server must answer from the headers.
+    let mut stream = TcpStream::connect(address).unwrap();
+    stream
+        .set_read_timeout(Some(Duration::from_secs(5)))
+        .unwrap();
+    write!(
+        stream,
+        "POST /v1/audio/transcriptions HTTP/1.1\r\nHost: localhost\r\nContent-Type: multipart/form-data; boundary=x\r\nContent-Length: 50000000\r\n\r\n"
+    )
+    .unwrap();
+    let mut status_line = [0u8; 12];
+    stream.read_exact(&mut status_line).unwrap();
+
+    assert_eq!(&status_line[9..12], b"503");
+    assert_eq!(busy.join().unwrap().0, 200);
+}
+
+#[test]
+fn engine_load_failure_fails_startup() {
+    let error = TranscriptionServer::start(
+        ServerConfig {
+            port: 0,
+            ..Default::default()
+        },
+        || Err("model not found".into()),
+    )
+    .err()
+    .expect("startup should fail");
+
+    assert!(error.to_string().contains("model not found"));
+}
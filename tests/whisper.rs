@@ -0,0 +1,87 @@
+#![cfg(feature = "whisper")]
+
+use std::path::Path;
+
+use glimpse_speech::engines::whisper::{WhisperEngine, WhisperModel};
+
+fn assert_send_sync<T: Send + Sync>() {}
+
+#[test]
+fn shared_model_and_sessions_cross_threads() {
+    assert_send_sync::<WhisperModel>();
+    assert_send_sync::<WhisperEngine>();
+}
+
+#[test]
+fn session_requires_a_loaded_model() {
+    let error = WhisperEngine::new()
+        .session()
+        .err()
+        .expect("an unloaded engine has no model to share");
+
+    assert!(error.to_string().contains("Model not loaded"));
+}
+
+#[test]
+fn missing_model_file_fails_to_load() {
+    assert!(WhisperModel::load(Path::new("does-not-exist.bin"), Default::default()).is_err());
+}
+
+#[test]
+fn rejects_a_buffer_that_is_not_a_model() {
+    let mut engine = WhisperEngine::new();
+
+    assert!(engine
+        .load_model_from_bytes(b"not a ggml model", Default::default())
+        .is_err());
+    assert!(engine.model().is_none());
+}
+
+#[cfg(unix)]
+#[test]
+fn non_utf8_model_paths_are_read_from_disk() {
+    use std::ffi::OsStr;
+    use std::os::unix::ffi::OsStrExt;
+
+    let path = std::env::temp_dir().join(OsStr::from_bytes(b"glimpse-model-\xff.bin"));
+    std::fs::write(&path, b"not a ggml model").unwrap();
+    let error = WhisperModel::load(&path, Default::default())
+        .err()
+        .expect("garbage is not a model");
+    let _ = std::fs::remove_file(&path);
+
+    assert!(!error.to_string().contains("UTF-8"));
+}
+
+#[test]
+fn reports_the_compiled_backend() {
+    let info = WhisperEngine::backend_info();
+
+    assert!(["cpu", "cuda", "vulkan", "metal"].contains(&info.backend));
+    if cfg!(not(any(
+        feature = "whisper-cuda",
+        feature = "whisper-vulkan"
+    ))) {
+        let expected = if cfg!(any(feature = "whisper-metal", target_os = "macos")) {
+            "metal"
+        } else {
+            "cpu"
+        };
+        assert_eq!(info.backend, expected);
+    }
+    assert!(info
+        .to_string()
+        .starts_with(&format!("backend={}", info.backend)));
+}
+
+#[test]
+fn streaming_requires_a_loaded_model() {
+    let mut seen = Vec::new();
+    let result =
+        WhisperEngine::new().transcribe_samples_with_callback(vec![0.0; 16_000], None, |segment| {
+            seen.push(segment.text.clone())
+        });
+
+    assert!(result.is_err());
+    assert!(seen.is_empty());
+}
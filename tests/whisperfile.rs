@@ -1,6 +1,14 @@
 #![cfg(feature = "whisperfile")]
 
-use glimpse_speech::engines::whisperfile::GPUMode;
+use std::net::TcpStream;
+use std::path::PathBuf;
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use glimpse_speech::engines::whisperfile::{
+    GPUMode, WhisperfileEngine, WhisperfileInferenceParams, WhisperfileModelParams,
+};
+use glimpse_speech::TranscriptionEngine;
 
 #[test]
 fn gpu_mode_values_are_stable() {
@@ -10,3 +18,189 @@ fn gpu_mode_values_are_stable() {
     assert_eq!(GPUMode::Nvidia.as_arg(), "nvidia");
     assert_eq!(GPUMode::Disabled.as_arg(), "disabled");
 }
+
+#[test]
+fn spawns_binary_with_model_and_gpu_args_and_posts_audio() {
+    let Some(binary) = fake_whisperfile() else {
+        return;
+    };
+    let model = temp_model("ok");
+
+    let mut engine = WhisperfileEngine::new(binary);
+    engine
+        .load_model_with_params(&model, test_model_params(GPUMode::Disabled))
+        .expect("fake whisperfile should become healthy");
+
+    let result = engine
+        .transcribe_samples(
+            vec![0.0; 1600],
+            Some(WhisperfileInferenceParams {
+                language: Some("de".to_string()),
+                ..Default::default()
+            }),
+        )
+        .expect("inference should succeed");
+
+    assert!(result.text.contains(r#""gpu": "disabled""#));
+    assert!(result.text.contains(&model.display().to_string()));
+    assert!(result.text.contains(r#""language": "de""#));
+    assert!(result.text.contains(r#""file": "<3244 bytes>""#));
+    assert_eq!(result.segments.map(|segments| segments.len()), Some(1));
+
+    let _ = std::fs::remove_file(model);
+}
+
+#[test]
+fn forwards_sampling_fields_and_parses_subtitle_responses() {
+    let Some(binary) = fake_whisperfile() else {
+        return;
+    };
+    let model = temp_model("formats");
+
+    let mut engine = WhisperfileEngine::new(binary);
+    engine
+        .load_model_with_params(&model, test_model_params(GPUMode::Auto))
+        .expect("fake whisperfile should become healthy");
+
+    let result = engine
+        .transcribe_samples(
+            vec![0.0; 160],
+            Some(WhisperfileInferenceParams {
+                translate: true,
+                temperature: Some(0.4),
+                response_format: Some("srt".to_string()),
+                ..Default::default()
+            }),
+        )
+        .expect("srt inference should succeed");
+
+    assert!(result.text.contains(r#""translate": "true""#));
+    assert!(result.text.contains(r#""temperature": "0.4""#));
+    assert!(result.text.contains(r#""response_format": "srt""#));
+    let segments = result.segments.expect("srt cues should become segments");
+    assert_eq!(segments.len(), 1);
+    assert_eq!(segments[0].end, 1.25);
+
+    let text = engine
+        .transcribe_samples(
+            vec![0.0; 160],
+            Some(WhisperfileInferenceParams {
+                response_format: Some("text".to_string()),
+                ..Default::default()
+            }),
+        )
+        .expect("text inference should succeed");
+    assert!(text.text.contains(r#""translate": "false""#));
+    assert_eq!(text.segments, None);
+
+    let _ = std::fs::remove_file(model);
+}
+
+#[test]
+fn unload_stops_the_server_process() {
+    let Some(binary) = fake_whisperfile() else {
+        return;
+    };
+    let model = temp_model("unload");
+
+    let mut engine = WhisperfileEngine::new(binary);
+    engine
+        .load_model_with_params(&model, test_model_params(GPUMode::Auto))
+        .expect("fake whisperfile should become healthy");
+    let address = engine
+        .server_url()
+        .expect("server should be running")
+        .trim_start_matches("http://")
+        .to_string();
+
+    engine.unload_model();
+
+    assert!(engine.server_url().is_none());
+    assert!(TcpStream::connect(&address).is_err());
+    let _ = std::fs::remove_file(model);
+}
+
+#[test]
+fn startup_timeout_is_enforced() {
+    let Some(binary) = fake_whisperfile() else {
+        return;
+    };
+    let model = temp_model("hang");
+
+    let mut engine = WhisperfileEngine::new(binary);
+    let error = engine
+        .load_model_with_params(
+            &model,
+            WhisperfileModelParams {
+                startup_timeout_secs: 1,
+                ..test_model_params(GPUMode::Auto)
+            },
+        )
+        .expect_err("server that never gets healthy must fail");
+
+    assert!(error.to_string().contains("did not become healthy"));
+    let _ = std::fs::remove_file(model);
+}
+
+#[test]
+fn picks_another_port_when_the_first_is_taken() {
+    let Some(binary) = fake_whisperfile() else {
+        return;
+    };
+    let model = temp_model("busy");
+    let marker = PathBuf::from(format!("{}.tried", model.display()));
+
+    let mut engine = WhisperfileEngine::new(binary);
+    engine
+        .load_model_with_params(&model, test_model_params(GPUMode::Auto))
+        .expect("second launch should bind a fresh port");
+
+    assert!(marker.exists(), "first launch should have failed to bind");
+    let _ = std::fs::remove_file(marker);
+    let _ = std::fs::remove_file(model);
+}
+
+#[test]
+fn early_exit_is_reported() {
+    let Some(binary) = fake_whisperfile() else {
+        return;
+    };
+    let model = temp_model("crash");
+
+    let mut engine = WhisperfileEngine::new(binary);
+    let error = engine
+        .load_model_with_params(&model, test_model_params(GPUMode::Auto))
+        .expect_err("crashing server must fail");
+
+    assert!(error.to_string().contains("exited during startup"));
+    let _ = std::fs::remove_file(model);
+}
+
+fn test_model_params(gpu: GPUMode) -> WhisperfileModelParams {
+    WhisperfileModelParams {
+        port: 0,
+        gpu,
+        startup_timeout_secs: 10,
+        ..Default::default()
+    }
+}
+
+fn fake_whisperfile() -> Option<PathBuf> {
+    // The stand-in server is a script run through its shebang line.
+    if cfg!(not(unix)) || Command::new("python3").arg("--version").output().is_err() {
+        eprintln!("python3 not found; skipping whisperfile process test");
+        return None;
+    }
+    Some(PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/fake_whisperfile.py"))
+}
+
+fn temp_model(kind: &str) -> PathBuf {
+    let nonce = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("clock should be monotonic")
+        .as_nanos();
+    let mut path = std::env::temp_dir();
+    path.push(format!("glimpse-speech-whisperfile-{kind}-{nonce}.bin"));
+    std::fs::write(&path, b"ggml").expect("model stub should be written");
+    path
+}